@@ -0,0 +1,134 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::PathBuf;
+
+use crate::CliError;
+
+pub(crate) const HELP: &str = "\
+Builds the gdext extension and runs gd-rehearse tests and benchmarks in headless Godot.
+
+Usage: cargo rehearse [OPTIONS] [FILTERS]... [-- RUNNER_ARGS...]
+
+Arguments:
+  [FILTERS]...            Run only cases with qualified names containing one of the filters
+  [RUNNER_ARGS]...        Arguments passed to `GdTestRunner` unchanged
+
+Options:
+  -p, --package <NAME>    Extension crate to build
+      --manifest-path <PATH>
+                          Path to `Cargo.toml` of the extension crate or its workspace
+      --release           Build the extension in release mode
+      --no-build          Don't build the extension before running Godot
+      --godot <PATH>      Godot executable. Defaults to `GODOT4_BIN` environment variable, `godot` key of the
+                          `gd-rehearse` metadata in `Cargo.toml` or `godot4`
+      --project <DIR>     Godot project directory. Defaults to `project` key of the metadata, or the first directory
+                          containing `project.godot` found in or above the current one, or in its `godot` subdirectory
+      --scene <PATH>      Scene with `GdTestRunner` to run, eg. `res://tests.tscn`. Defaults to the main scene
+      --test              Run only tests
+      --bench             Run only benchmarks
+      --process-per-case  Run every case in separate Godot process, so a crash doesn't stop the whole run
+      --process-per-file  Run cases of every file in separate Godot process
+  -w, --watch             Rebuild and rerun the cases declared in changed `.rs` files, until `q` is entered. Entering `a`
+                          runs all cases
+  -q, --quiet             Print only the summary
+  -h, --help              Print help
+
+Other options starting with `--`, like `--tags=tag1,tag2` or `--shuffle`, are passed to `GdTestRunner`.";
+
+/// Granularity of running cases in separate Godot processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProcessPer {
+    Case,
+    File,
+}
+
+/// Arguments of the subcommand, with the ones for `GdTestRunner` already translated.
+#[derive(Debug, Default)]
+pub(crate) struct Args {
+    pub(crate) package: Option<String>,
+    pub(crate) manifest_path: Option<PathBuf>,
+    pub(crate) release: bool,
+    pub(crate) no_build: bool,
+    pub(crate) godot: Option<PathBuf>,
+    pub(crate) project: Option<PathBuf>,
+    pub(crate) scene: Option<String>,
+    pub(crate) process_per: Option<ProcessPer>,
+    pub(crate) watch: bool,
+    pub(crate) runner_args: Vec<String>,
+    pub(crate) help: bool,
+}
+
+impl Args {
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut args = args.into_iter().peekable();
+        // Invoked by cargo as `cargo-rehearse rehearse [args]`.
+        if args.peek().map(String::as_str) == Some("rehearse") {
+            args.next();
+        }
+
+        let mut parsed = Self::default();
+        let mut filters = Vec::new();
+        let mut passthrough = Vec::new();
+
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| CliError::usage(format!("`{name}` requires a value")))
+            };
+
+            match name {
+                "-p" | "--package" => parsed.package = Some(value()?),
+                "--manifest-path" => parsed.manifest_path = Some(value()?.into()),
+                "--release" => parsed.release = true,
+                "--no-build" => parsed.no_build = true,
+                "--godot" => parsed.godot = Some(value()?.into()),
+                "--project" => parsed.project = Some(value()?.into()),
+                "--scene" => parsed.scene = Some(value()?),
+                "--test" => parsed.runner_args.push("--rust-test".to_owned()),
+                "--bench" => parsed.runner_args.push("--rust-bench".to_owned()),
+                "-q" | "--quiet" => parsed.runner_args.push("--quiet-run".to_owned()),
+                "--process-per-case" => parsed.set_process_per(ProcessPer::Case)?,
+                "--process-per-file" => parsed.set_process_per(ProcessPer::File)?,
+                "-w" | "--watch" => parsed.watch = true,
+                "-h" | "--help" => parsed.help = true,
+                "--" => passthrough.extend(args.by_ref()),
+                _ if arg.starts_with("--") => parsed.runner_args.push(arg.clone()),
+                _ if arg.starts_with('-') => {
+                    return Err(CliError::usage(format!("unrecognized option `{arg}`")))
+                }
+                _ => filters.push(arg.clone()),
+            }
+        }
+
+        if !filters.is_empty() {
+            parsed
+                .runner_args
+                .push(format!("--filters={}", filters.join(",")));
+        }
+        parsed.runner_args.extend(passthrough);
+        Ok(parsed)
+    }
+
+    fn set_process_per(&mut self, process_per: ProcessPer) -> Result<(), CliError> {
+        if self
+            .process_per
+            .is_some_and(|current| current != process_per)
+        {
+            return Err(CliError::usage(
+                "`--process-per-case` and `--process-per-file` are mutually exclusive",
+            ));
+        }
+        self.process_per = Some(process_per);
+        Ok(())
+    }
+}
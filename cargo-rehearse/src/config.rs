@@ -0,0 +1,150 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::{Path, PathBuf};
+
+use crate::args::Args;
+use crate::CliError;
+
+pub(crate) const GODOT_BIN_ENV: &str = "GODOT4_BIN";
+const DEFAULT_GODOT_BIN: &str = "godot4";
+const METADATA_KEY: &str = "gd-rehearse";
+
+/// Settings read from `[package.metadata.gd-rehearse]` or `[workspace.metadata.gd-rehearse]` table of `Cargo.toml`. Paths are
+/// relative to the manifest directory.
+///
+/// ```toml
+/// [workspace.metadata.gd-rehearse]
+/// package = "tests"
+/// project = "tests/godot"
+/// godot = "/opt/godot/godot4"
+/// scene = "res://tests.tscn"
+/// ```
+#[derive(Debug, Default)]
+struct Metadata {
+    package: Option<String>,
+    godot: Option<PathBuf>,
+    project: Option<PathBuf>,
+    scene: Option<String>,
+}
+
+impl Metadata {
+    fn read(manifest_path: &Path) -> Result<Self, CliError> {
+        let Ok(content) = std::fs::read_to_string(manifest_path) else {
+            return Ok(Self::default());
+        };
+        let manifest = content.parse::<toml::Table>().map_err(|err| {
+            CliError::usage(format!("cannot parse `{}`: {err}", manifest_path.display()))
+        })?;
+
+        let table = ["package", "workspace"].iter().find_map(|section| {
+            manifest
+                .get(*section)?
+                .get("metadata")?
+                .get(METADATA_KEY)?
+                .as_table()
+        });
+        let Some(table) = table else {
+            return Ok(Self::default());
+        };
+
+        let manifest_dir = manifest_path.parent().unwrap_or(Path::new("."));
+        let string = |key: &str| -> Result<Option<String>, CliError> {
+            match table.get(key) {
+                None => Ok(None),
+                Some(toml::Value::String(value)) => Ok(Some(value.clone())),
+                Some(_) => Err(CliError::usage(format!(
+                    "`{key}` in `{METADATA_KEY}` metadata of `{}` needs to be a string",
+                    manifest_path.display()
+                ))),
+            }
+        };
+
+        Ok(Self {
+            package: string("package")?,
+            godot: string("godot")?.map(|path| {
+                // Bare executable name is searched in `PATH`.
+                let path = PathBuf::from(path);
+                if path.components().count() > 1 {
+                    manifest_dir.join(path)
+                } else {
+                    path
+                }
+            }),
+            project: string("project")?.map(|path| manifest_dir.join(path)),
+            scene: string("scene")?,
+        })
+    }
+}
+
+/// Resolved settings of the run.
+#[derive(Debug)]
+pub(crate) struct Settings {
+    pub(crate) manifest_path: Option<PathBuf>,
+    /// Directory of the manifest, watched in watch mode.
+    pub(crate) crate_dir: PathBuf,
+    pub(crate) package: Option<String>,
+    pub(crate) release: bool,
+    pub(crate) godot: PathBuf,
+    pub(crate) project: PathBuf,
+    pub(crate) scene: Option<String>,
+}
+
+impl Settings {
+    /// Command line arguments take precedence over `GODOT4_BIN` environment variable, which takes precedence over the metadata.
+    pub(crate) fn resolve(args: &Args) -> Result<Self, CliError> {
+        let current_dir = std::env::current_dir()
+            .map_err(|err| CliError::usage(format!("cannot access current directory: {err}")))?;
+        let manifest_path = args
+            .manifest_path
+            .clone()
+            .unwrap_or_else(|| current_dir.join("Cargo.toml"));
+        let metadata = Metadata::read(&manifest_path)?;
+
+        let godot = args
+            .godot
+            .clone()
+            .or_else(|| std::env::var_os(GODOT_BIN_ENV).map(PathBuf::from))
+            .or(metadata.godot)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_GODOT_BIN));
+
+        let search_from = manifest_path.parent().unwrap_or(&current_dir);
+        let project = args
+            .project
+            .clone()
+            .or(metadata.project)
+            .or_else(|| find_project(search_from))
+            .ok_or_else(|| {
+                CliError::usage(format!(
+                    "cannot find Godot project in or above `{}`. Specify it with `--project`",
+                    search_from.display()
+                ))
+            })?;
+        if !project.join("project.godot").is_file() {
+            return Err(CliError::usage(format!(
+                "`{}` is not a Godot project directory",
+                project.display()
+            )));
+        }
+
+        Ok(Self {
+            manifest_path: args.manifest_path.clone(),
+            crate_dir: search_from.to_path_buf(),
+            package: args.package.clone().or(metadata.package),
+            release: args.release,
+            godot,
+            project,
+            scene: args.scene.clone().or(metadata.scene),
+        })
+    }
+}
+
+fn find_project(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .flat_map(|dir| [dir.to_path_buf(), dir.join("godot")])
+        .find(|dir| dir.join("project.godot").is_file())
+}
@@ -0,0 +1,97 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::OsString;
+use std::process::{Command, ExitStatus, Output, Stdio};
+
+use crate::config::{Settings, GODOT_BIN_ENV};
+use crate::CliError;
+
+/// Builds the extension with the same `cargo` which invoked the subcommand.
+pub(crate) fn build_extension(settings: &Settings) -> Result<(), CliError> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let mut command = Command::new(cargo);
+    command.arg("build");
+    if let Some(manifest_path) = &settings.manifest_path {
+        command.arg("--manifest-path").arg(manifest_path);
+    }
+    if let Some(package) = &settings.package {
+        command.args(["--package", package]);
+    }
+    if settings.release {
+        command.arg("--release");
+    }
+
+    // Standard input is left for the commands of watch mode.
+    let status = command
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|err| CliError::usage(format!("cannot run cargo: {err}")))?;
+    if !status.success() {
+        return Err(CliError::new(
+            "building the extension failed",
+            status.code().unwrap_or(1),
+        ));
+    }
+    Ok(())
+}
+
+/// Runs the project in headless Godot, with output streamed to the terminal. Returns the exit code of the runner.
+pub(crate) fn run_godot(settings: &Settings, runner_args: &[String]) -> Result<i32, CliError> {
+    let status = godot_status(settings, runner_args)?;
+    // Godot killed by a signal doesn't have an exit code.
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Runs the project in headless Godot, with output streamed to the terminal.
+pub(crate) fn godot_status(
+    settings: &Settings,
+    runner_args: &[String],
+) -> Result<ExitStatus, CliError> {
+    let mut command = godot_command(settings, runner_args);
+    eprintln!(
+        "     Running `{} {}`",
+        settings.godot.display(),
+        command
+            .get_args()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    command.status().map_err(|err| launch_error(settings, err))
+}
+
+/// Runs the project in headless Godot, capturing its standard output.
+pub(crate) fn godot_output(
+    settings: &Settings,
+    runner_args: &[String],
+) -> Result<Output, CliError> {
+    godot_command(settings, runner_args)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| launch_error(settings, err))
+}
+
+fn godot_command(settings: &Settings, runner_args: &[String]) -> Command {
+    let mut command = Command::new(&settings.godot);
+    command.arg("--path").arg(&settings.project);
+    if let Some(scene) = &settings.scene {
+        command.arg(scene);
+    }
+    command
+        .arg("--headless")
+        .arg("--")
+        .args(runner_args)
+        .stdin(Stdio::null());
+    command
+}
+
+fn launch_error(settings: &Settings, err: std::io::Error) -> CliError {
+    CliError::usage(format!(
+        "cannot launch Godot executable `{}`: {err}. Specify it with `--godot` or `{GODOT_BIN_ENV}` environment variable",
+        settings.godot.display()
+    ))
+}
@@ -0,0 +1,85 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! `cargo rehearse` subcommand: builds the `gdext` extension crate, launches Godot in headless mode with the scene containing
+//! `GdTestRunner` and returns its exit code.
+//!
+//! ```no_compile
+//! cargo rehearse -p my_extension --tags=physics my_filter -- --shuffle
+//! ```
+//!
+//! Run `cargo rehearse --help` for all options.
+
+use std::fmt::Display;
+use std::process::ExitCode;
+
+mod args;
+mod config;
+mod launch;
+mod supervisor;
+mod watch;
+
+use args::{Args, HELP};
+use config::Settings;
+
+/// Exit code for errors of the subcommand itself, distinguishing them from failed runs.
+const USAGE_ERROR_CODE: i32 = 2;
+
+#[derive(Debug)]
+pub(crate) struct CliError {
+    message: String,
+    exit_code: i32,
+}
+
+impl CliError {
+    pub(crate) fn new(message: impl Into<String>, exit_code: i32) -> Self {
+        Self {
+            message: message.into(),
+            exit_code,
+        }
+    }
+
+    pub(crate) fn usage(message: impl Into<String>) -> Self {
+        Self::new(message, USAGE_ERROR_CODE)
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+fn run() -> Result<i32, CliError> {
+    let args = Args::parse(std::env::args().skip(1))?;
+    if args.help {
+        println!("{HELP}");
+        return Ok(0);
+    }
+
+    let settings = Settings::resolve(&args)?;
+    if args.watch {
+        return watch::watch(&settings, &args);
+    }
+    if !args.no_build {
+        launch::build_extension(&settings)?;
+    }
+    match args.process_per {
+        Some(process_per) => supervisor::run_separately(&settings, &args.runner_args, process_per),
+        None => launch::run_godot(&settings, &args.runner_args),
+    }
+}
+
+fn main() -> ExitCode {
+    let code = run().unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        err.exit_code
+    });
+    // Codes which don't fit, like the negative ones of crashed processes on Windows, still need to report failure.
+    ExitCode::from(u8::try_from(code).unwrap_or(1))
+}
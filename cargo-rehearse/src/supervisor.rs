@@ -0,0 +1,203 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Running every case or file in separate Godot process, so a crash inside one of them doesn't lose the results of the others.
+
+use std::process::ExitStatus;
+
+use crate::args::ProcessPer;
+use crate::config::Settings;
+use crate::launch::{godot_output, godot_status};
+use crate::CliError;
+
+/// Prefix of the lines printed by `GdTestRunner` with `--list-cases`.
+const LIST_CASES_PREFIX: &str = "gd-rehearse-case";
+
+pub(crate) struct ListedCase {
+    pub(crate) kind: String,
+    name: String,
+    file: String,
+    /// Case is selected, but the runner would skip it, failing the process which runs only skipped cases.
+    pub(crate) skipped: bool,
+}
+
+/// Cases or file run in one process.
+struct Unit {
+    label: String,
+    args: Vec<String>,
+}
+
+/// Lists the cases selected by `runner_args`, then runs them in separate processes and summarizes their results. Processes which
+/// ended with other exit code than the runner uses, or were killed by a signal, are reported as crashed. Skipped cases aren't run,
+/// only counted.
+pub(crate) fn run_separately(
+    settings: &Settings,
+    runner_args: &[String],
+    process_per: ProcessPer,
+) -> Result<i32, CliError> {
+    let (skipped, cases): (Vec<_>, Vec<_>) = list_cases(settings, runner_args)?
+        .into_iter()
+        .partition(|case| case.skipped);
+    let units = split_into_units(cases, process_per);
+
+    let mut passed = 0;
+    let mut failed = Vec::new();
+    let mut crashed = Vec::new();
+    for unit in units.iter() {
+        // Units select the kind of their cases, which the runner doesn't accept twice.
+        let mut args = without_kind_args(runner_args);
+        args.extend(unit.args.iter().cloned());
+        let status = godot_status(settings, &args)?;
+        match status.code() {
+            Some(0) => passed += 1,
+            Some(1) => failed.push(unit.label.clone()),
+            _ => crashed.push(format!("{} ({})", unit.label, describe_crash(&status))),
+        }
+    }
+
+    let outcome = if failed.is_empty() && crashed.is_empty() {
+        "ok!"
+    } else {
+        "FAILED!"
+    };
+    let kind = match process_per {
+        ProcessPer::Case => "case",
+        ProcessPer::File => "file",
+    };
+    let extra = if skipped.is_empty() {
+        String::new()
+    } else {
+        format!("; {} skipped cases", skipped.len())
+    };
+    println!(
+        "\nProcess-per-{kind} result: {outcome} {passed} passed; {} failed; {} crashed{extra}.",
+        failed.len(),
+        crashed.len()
+    );
+    print_list("Failed", &failed);
+    print_list("Crashed", &crashed);
+
+    Ok(if outcome == "ok!" { 0 } else { 1 })
+}
+
+/// Runner arguments without the ones selecting the kind of cases to run.
+pub(crate) fn without_kind_args(runner_args: &[String]) -> Vec<String> {
+    runner_args
+        .iter()
+        .filter(|arg| !matches!(arg.as_str(), "--rust-test" | "--rust-bench"))
+        .cloned()
+        .collect()
+}
+
+/// Cases selected by `runner_args`, listed by the runner with `--list-cases`.
+pub(crate) fn list_cases(
+    settings: &Settings,
+    runner_args: &[String],
+) -> Result<Vec<ListedCase>, CliError> {
+    let mut args = runner_args.to_vec();
+    args.push("--list-cases".to_owned());
+    let output = godot_output(settings, &args)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        eprint!("{stdout}");
+        return Err(CliError::new(
+            "listing the cases failed",
+            output.status.code().unwrap_or(1),
+        ));
+    }
+
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim_end().split('\t');
+            if fields.next()? != LIST_CASES_PREFIX {
+                return None;
+            }
+            Some(ListedCase {
+                kind: fields.next()?.to_owned(),
+                name: fields.next()?.to_owned(),
+                file: fields.next()?.to_owned(),
+                skipped: fields.next() == Some("skipped"),
+            })
+        })
+        .collect())
+}
+
+// Only the kind of the unit cases is run, as the runner fails if no cases of enabled kind were run.
+fn split_into_units(cases: Vec<ListedCase>, process_per: ProcessPer) -> Vec<Unit> {
+    match process_per {
+        ProcessPer::Case => cases
+            .into_iter()
+            .map(|case| Unit {
+                label: format!("{} {}", case.kind, case.name),
+                args: vec![
+                    kind_arg(&case.kind).to_owned(),
+                    format!("--only-case={}", case.name),
+                ],
+            })
+            .collect(),
+        ProcessPer::File => {
+            let mut files: Vec<(String, String)> = Vec::new();
+            for case in cases {
+                let file = (case.file, case.kind);
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+            files
+                .into_iter()
+                .map(|(file, kind)| Unit {
+                    args: vec![kind_arg(&kind).to_owned(), format!("--only-file={file}")],
+                    label: format!("{kind} {file}"),
+                })
+                .collect()
+        }
+    }
+}
+
+fn kind_arg(kind: &str) -> &'static str {
+    if kind == "bench" {
+        "--rust-bench"
+    } else {
+        "--rust-test"
+    }
+}
+
+fn print_list(header: &str, list: &[String]) {
+    if list.is_empty() {
+        return;
+    }
+    println!("\n  {header}:");
+    for item in list {
+        println!("  * {item}");
+    }
+}
+
+#[cfg(unix)]
+fn describe_crash(status: &ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+
+    let Some(signal) = status.signal() else {
+        return format!("exit code {}", status.code().unwrap_or_default());
+    };
+    let name = match signal {
+        4 => " SIGILL",
+        6 => " SIGABRT",
+        8 => " SIGFPE",
+        9 => " SIGKILL",
+        11 => " SIGSEGV",
+        _ => "",
+    };
+    format!("signal {signal}{name}")
+}
+
+#[cfg(not(unix))]
+fn describe_crash(status: &ExitStatus) -> String {
+    match status.code() {
+        Some(code) => format!("exit code {code:#x}"),
+        None => "no exit code".to_owned(),
+    }
+}
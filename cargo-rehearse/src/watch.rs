@@ -0,0 +1,192 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Watch mode: rebuilds the extension after its sources change, and reruns only the cases declared in the changed files.
+
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+use crate::args::Args;
+use crate::config::Settings;
+use crate::launch::{build_extension, run_godot};
+use crate::{supervisor, CliError};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Modification time and length of every watched file.
+type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+enum UserCommand {
+    RunAll,
+    Quit,
+}
+
+/// Runs all cases, then keeps rerunning the cases from changed files until `q` is entered. Entering `a` or empty line runs all cases.
+pub(crate) fn watch(settings: &Settings, args: &Args) -> Result<i32, CliError> {
+    let root = settings.crate_dir.as_path();
+    let commands = spawn_command_reader();
+    let mut snapshot = take_snapshot(root);
+
+    eprintln!("    Watching `.rs` files in `{}`", root.display());
+    rebuild_and_run(settings, args, &[]);
+
+    loop {
+        match commands.recv_timeout(POLL_INTERVAL) {
+            Ok(UserCommand::Quit) => return Ok(0),
+            Ok(UserCommand::RunAll) => {
+                snapshot = take_snapshot(root);
+                rebuild_and_run(settings, args, &[]);
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // Without standard input only the changes are watched.
+            Err(RecvTimeoutError::Disconnected) => std::thread::sleep(POLL_INTERVAL),
+        }
+
+        let current = take_snapshot(root);
+        if current == snapshot {
+            continue;
+        }
+        let changed = changed_files(root, &snapshot, &current);
+        snapshot = current;
+        if !changed.is_empty() {
+            eprintln!("     Changed {}", changed.join(", "));
+        }
+        // Only removed files leave nothing to filter on, so all cases are run.
+        rebuild_and_run(settings, args, &changed);
+    }
+}
+
+fn rebuild_and_run(settings: &Settings, args: &Args, changed: &[String]) {
+    if !args.no_build {
+        if let Err(err) = build_extension(settings) {
+            eprintln!("error: {err}");
+            print_prompt();
+            return;
+        }
+    }
+
+    let mut runner_args = args.runner_args.clone();
+    if !changed.is_empty() {
+        match select_changed_cases(settings, &runner_args, changed) {
+            Ok(Some(selection)) => runner_args = selection,
+            Ok(None) => eprintln!("     No cases declared in changed files, running all cases"),
+            Err(err) => {
+                eprintln!("error: {err}");
+                print_prompt();
+                return;
+            }
+        }
+    }
+    let result = match args.process_per {
+        Some(process_per) => supervisor::run_separately(settings, &runner_args, process_per),
+        None => run_godot(settings, &runner_args),
+    };
+    if let Err(err) = result {
+        eprintln!("error: {err}");
+    }
+    print_prompt();
+}
+
+/// Runner arguments selecting the cases declared in `changed` files, or `None` if there are no such cases to run. Only the kinds of
+/// these cases are enabled, as the runner fails if no cases of enabled kind were run.
+fn select_changed_cases(
+    settings: &Settings,
+    runner_args: &[String],
+    changed: &[String],
+) -> Result<Option<Vec<String>>, CliError> {
+    let mut listing_args = runner_args.to_vec();
+    listing_args.push(format!("--files={}", changed.join(",")));
+    let cases = supervisor::list_cases(settings, &listing_args)?;
+
+    let mut selection = supervisor::without_kind_args(&listing_args);
+    let has_kind = |kind: &str| cases.iter().any(|case| case.kind == kind && !case.skipped);
+    let has_tests = has_kind("test");
+    let has_benches = has_kind("bench");
+    match (has_tests, has_benches) {
+        (false, false) => return Ok(None),
+        (true, false) => selection.push("--rust-test".to_owned()),
+        (false, true) => selection.push("--rust-bench".to_owned()),
+        (true, true) => {}
+    }
+    Ok(Some(selection))
+}
+
+fn print_prompt() {
+    eprintln!("    Waiting for changes. Enter `a` to run all cases or `q` to quit");
+}
+
+fn spawn_command_reader() -> Receiver<UserCommand> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            let command = match line.trim() {
+                "" | "a" | "all" => UserCommand::RunAll,
+                "q" | "quit" => UserCommand::Quit,
+                other => {
+                    eprintln!(
+                        "unknown command `{other}`. Enter `a` to run all cases or `q` to quit"
+                    );
+                    continue;
+                }
+            };
+            if sender.send(command).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn take_snapshot(root: &Path) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    collect_sources(root, &mut snapshot);
+    snapshot
+}
+
+// Build output and hidden directories, like `.git` or `.godot`, are skipped.
+fn collect_sources(dir: &Path, snapshot: &mut Snapshot) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_sources(&path, snapshot);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            snapshot.insert(path, (modified, metadata.len()));
+        }
+    }
+}
+
+/// Added or modified files, relative to the watched directory.
+fn changed_files(root: &Path, previous: &Snapshot, current: &Snapshot) -> Vec<String> {
+    current
+        .iter()
+        .filter(|(path, state)| previous.get(*path) != Some(state))
+        .map(|(path, _)| {
+            path.strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect()
+}
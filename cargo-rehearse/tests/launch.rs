@@ -0,0 +1,339 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+// Stub script stands in for Godot and cargo executables, printing the arguments it received.
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+fn crate_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn stub() -> PathBuf {
+    crate_dir().join("tests/stub/godot.sh")
+}
+
+fn workspace_manifest() -> PathBuf {
+    crate_dir().join("../Cargo.toml")
+}
+
+fn godot_project() -> PathBuf {
+    crate_dir().join("../tests/godot")
+}
+
+fn rehearse(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_cargo-rehearse"));
+    command
+        .arg("rehearse")
+        .args(args)
+        .env_remove("GODOT4_BIN")
+        .env("CARGO", stub());
+    command
+}
+
+fn stdout_lines(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+#[test]
+fn translates_runner_arguments() {
+    let output = rehearse(&[
+        "--no-build",
+        "--godot",
+        stub().to_str().unwrap(),
+        "--project",
+        godot_project().to_str().unwrap(),
+        "--scene=res://test.tscn",
+        "--bench",
+        "-q",
+        "--tags=physics,slow",
+        "filter_me",
+        "second",
+        "--",
+        "--shuffle",
+    ])
+    .output()
+    .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        [format!(
+            "stub --path {} res://test.tscn --headless -- --rust-bench --quiet-run --tags=physics,slow \
+            --filters=filter_me,second --shuffle",
+            godot_project().display()
+        )]
+    );
+}
+
+#[test]
+fn builds_extension_from_metadata() {
+    let output = rehearse(&[
+        "--manifest-path",
+        workspace_manifest().to_str().unwrap(),
+        "--godot",
+        stub().to_str().unwrap(),
+        "--release",
+    ])
+    .output()
+    .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_lines(&output),
+        [
+            format!(
+                "stub build --manifest-path {} --package tests --release",
+                workspace_manifest().display()
+            ),
+            format!(
+                "stub --path {} --headless --",
+                crate_dir().join("../tests/godot").display()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn returns_runner_exit_code() {
+    let output = rehearse(&["--no-build", "--project", godot_project().to_str().unwrap()])
+        .env("GODOT4_BIN", stub())
+        .env("STUB_EXIT_CODE", "3")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout_lines(&output).len(), 1);
+}
+
+#[test]
+fn failed_build_skips_godot() {
+    let output = rehearse(&["--godot", stub().to_str().unwrap()])
+        .current_dir(godot_project())
+        .env("STUB_EXIT_CODE", "101")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(101));
+    assert_eq!(stdout_lines(&output), ["stub build"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("building the extension failed"));
+}
+
+#[test]
+fn missing_project_is_usage_error() {
+    let output = rehearse(&["--no-build", "--project", crate_dir().to_str().unwrap()])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not a Godot project directory"));
+}
+
+const STUB_CASES: &str = "gd-rehearse-case\ttest\ttests::itest::first\tsrc/itest.rs\n\
+    gd-rehearse-case\ttest\ttests::itest::crashing\tsrc/itest.rs\n\
+    gd-rehearse-case\ttest\ttests::suite::failing\tsrc/suite.rs\n\
+    gd-rehearse-case\tbench\ttests::bench::normal\tsrc/bench.rs\n";
+
+#[test]
+fn process_per_case_reports_crashes() {
+    let output = rehearse(&[
+        "--no-build",
+        "--godot",
+        stub().to_str().unwrap(),
+        "--project",
+        godot_project().to_str().unwrap(),
+        "--process-per-case",
+        "--isolate",
+        "--test",
+        "--bench",
+    ])
+    .env("STUB_CASES", STUB_CASES)
+    .env("STUB_FAIL", "tests::suite::failing")
+    .env("STUB_CRASH", "tests::itest::crashing")
+    .output()
+    .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = stdout_lines(&output);
+    let path = godot_project().display().to_string();
+    // Output of listing the cases is captured, so only the runs of the cases are printed.
+    let runs = stdout
+        .iter()
+        .filter(|line| line.starts_with("stub"))
+        .cloned()
+        .collect::<Vec<_>>();
+    // Kind selected by the user is replaced with the kind of the case.
+    assert_eq!(
+        runs,
+        [
+            format!("stub --path {path} --headless -- --isolate --rust-test --only-case=tests::itest::first"),
+            format!("stub --path {path} --headless -- --isolate --rust-test --only-case=tests::itest::crashing"),
+            format!("stub --path {path} --headless -- --isolate --rust-test --only-case=tests::suite::failing"),
+            format!("stub --path {path} --headless -- --isolate --rust-bench --only-case=tests::bench::normal"),
+        ]
+    );
+    let summary = stdout.join("\n");
+    assert!(summary.contains("Process-per-case result: FAILED! 2 passed; 1 failed; 1 crashed."));
+    assert!(summary.contains("* test tests::suite::failing"));
+    assert!(summary.contains("* test tests::itest::crashing (signal 6 SIGABRT)"));
+}
+
+#[test]
+fn process_per_case_counts_skipped_cases() {
+    let output = rehearse(&[
+        "--no-build",
+        "--godot",
+        stub().to_str().unwrap(),
+        "--project",
+        godot_project().to_str().unwrap(),
+        "--process-per-case",
+    ])
+    .env(
+        "STUB_CASES",
+        "gd-rehearse-case\ttest\ttests::itest::simple_test\tsrc/itest.rs\n\
+        gd-rehearse-case\ttest\ttests::itest::skipped_test\tsrc/itest.rs\tskipped\n",
+    )
+    // Runner fails when only skipped cases are selected.
+    .env("STUB_FAIL", "tests::itest::skipped_test")
+    .output()
+    .unwrap();
+
+    assert!(output.status.success());
+    let stdout = stdout_lines(&output);
+    assert!(!stdout
+        .iter()
+        .any(|line| line.contains("--only-case=tests::itest::skipped_test")));
+    assert!(stdout
+        .join("\n")
+        .contains("Process-per-case result: ok! 1 passed; 0 failed; 0 crashed; 1 skipped cases."));
+}
+
+fn process_per_file_runs(cases: &str) -> (Output, Vec<String>) {
+    let output = rehearse(&[
+        "--no-build",
+        "--godot",
+        stub().to_str().unwrap(),
+        "--project",
+        godot_project().to_str().unwrap(),
+        "--process-per-file",
+    ])
+    .env("STUB_CASES", cases)
+    .output()
+    .unwrap();
+    // Arguments after `--`, from the runs of the files.
+    let runs = stdout_lines(&output)
+        .iter()
+        .filter(|line| line.contains("--only-file="))
+        .map(|line| line.split_once(" -- ").unwrap().1.to_owned())
+        .collect();
+    (output, runs)
+}
+
+#[test]
+fn process_per_file_runs_every_file_once() {
+    let (output, runs) = process_per_file_runs(STUB_CASES);
+
+    assert!(output.status.success());
+    // Files contain only one kind of cases, so the other kind isn't run.
+    assert_eq!(
+        runs,
+        [
+            "--rust-test --only-file=src/itest.rs",
+            "--rust-test --only-file=src/suite.rs",
+            "--rust-bench --only-file=src/bench.rs"
+        ]
+    );
+    assert!(stdout_lines(&output)
+        .join("\n")
+        .contains("Process-per-file result: ok! 3 passed; 0 failed; 0 crashed."));
+}
+
+#[test]
+fn process_per_file_runs_every_kind_separately() {
+    let (output, runs) = process_per_file_runs(
+        "gd-rehearse-case\ttest\ttests::mixed::test\tsrc/mixed.rs\n\
+        gd-rehearse-case\tbench\ttests::mixed::bench\tsrc/mixed.rs\n\
+        gd-rehearse-case\ttest\ttests::mixed::other_test\tsrc/mixed.rs\n",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        runs,
+        [
+            "--rust-test --only-file=src/mixed.rs",
+            "--rust-bench --only-file=src/mixed.rs"
+        ]
+    );
+}
+
+#[test]
+fn process_per_file_omits_files_with_only_skipped_cases() {
+    let (output, runs) = process_per_file_runs(
+        "gd-rehearse-case\ttest\ttests::itest::simple_test\tsrc/itest.rs\n\
+        gd-rehearse-case\tbench\ttests::itest::skipped_bench\tsrc/itest.rs\tskipped\n\
+        gd-rehearse-case\ttest\ttests::skipped::test\tsrc/skipped.rs\tskipped\n",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(runs, ["--rust-test --only-file=src/itest.rs"]);
+}
+
+#[test]
+fn watch_reruns_cases_from_changed_files() {
+    let project = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("watched_project");
+    let _ = std::fs::remove_dir_all(&project);
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    std::fs::write(project.join("project.godot"), "").unwrap();
+    std::fs::write(project.join("src/itest.rs"), "// tests").unwrap();
+
+    let mut child = rehearse(&["--watch", "--no-build", "--godot", stub().to_str().unwrap()])
+        .current_dir(&project)
+        .env(
+            "STUB_CASES",
+            "gd-rehearse-case\ttest\ttests::itest::first\tsrc/itest.rs\n",
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let (sender, lines) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let _ = sender.send(line.unwrap());
+        }
+    });
+    let next_run = || lines.recv_timeout(Duration::from_secs(10)).unwrap();
+    let path = project.display().to_string();
+
+    assert_eq!(next_run(), format!("stub --path {path} --headless --"));
+
+    // Only tests are declared in the changed file, so benchmarks aren't run.
+    std::fs::write(project.join("src/itest.rs"), "// changed tests").unwrap();
+    assert_eq!(
+        next_run(),
+        format!("stub --path {path} --headless -- --files=src/itest.rs --rust-test")
+    );
+
+    // No cases are declared in the changed file, so all cases are run.
+    std::fs::write(project.join("src/lib.rs"), "// implementation").unwrap();
+    assert_eq!(next_run(), format!("stub --path {path} --headless --"));
+
+    writeln!(stdin, "a").unwrap();
+    assert_eq!(next_run(), format!("stub --path {path} --headless --"));
+
+    writeln!(stdin, "q").unwrap();
+    assert!(child.wait().unwrap().success());
+}
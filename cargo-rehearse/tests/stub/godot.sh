@@ -0,0 +1,30 @@
+#!/bin/sh
+# Stands in for Godot and cargo executables: prints received arguments and exits with `STUB_EXIT_CODE`.
+# With `--list-cases` prints `STUB_CASES`, only the ones declared in the file given with `--files`. Case or file named in `STUB_FAIL`
+# fails, and the one named in `STUB_CRASH` aborts.
+echo "stub $*"
+files=""
+for arg in "$@"; do
+  case "$arg" in
+    --files=*)
+      files="${arg#*=}"
+      ;;
+  esac
+done
+for arg in "$@"; do
+  case "$arg" in
+    --list-cases)
+      printf '%b' "$STUB_CASES" | grep -F -e "$files" || true
+      ;;
+    --only-case=*|--only-file=*)
+      name="${arg#*=}"
+      if [ "$name" = "$STUB_CRASH" ]; then
+        kill -ABRT $$
+      fi
+      if [ "$name" = "$STUB_FAIL" ]; then
+        exit 1
+      fi
+      ;;
+  esac
+done
+exit "${STUB_EXIT_CODE:-0}"
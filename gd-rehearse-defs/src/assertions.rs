@@ -0,0 +1,424 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Godot-aware assertions, producing diffs naming the differing keys, indices or components.
+
+use godot::builtin::{
+    Basis, Color, Dictionary, Quaternion, Transform2D, Transform3D, Variant, VariantArray,
+    VariantType, Vector2, Vector3, Vector4,
+};
+
+/// Default tolerance used by [`assert_approx_eq!`](crate::assert_approx_eq).
+pub const DEFAULT_EPSILON: f64 = 1e-5;
+
+/// Asserts that two values are equal after converting them to `Variant`.
+///
+/// On failure, nested `Dictionary` and `Array` values are compared element by element, and every difference is listed with its path.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_rehearse::itest::*;
+///
+/// #[gditest]
+/// fn stats_are_saved() {
+///     let saved = vdict! { "hp": 10, "items": varray!["sword", "shield"] };
+///     assert_variant_eq!(saved, vdict! { "hp": 10, "items": varray!["sword", "shield"] });
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_variant_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assertions::variant_eq_impl(
+            &::godot::meta::ToGodot::to_variant(&$left),
+            &::godot::meta::ToGodot::to_variant(&$right),
+            None,
+        )
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::assertions::variant_eq_impl(
+            &::godot::meta::ToGodot::to_variant(&$left),
+            &::godot::meta::ToGodot::to_variant(&$right),
+            Some(format!($($arg)+)),
+        )
+    };
+}
+
+/// Asserts that two floating-point based values are equal within tolerance.
+///
+/// Works with every type implementing [`ApproxComponents`](crate::assertions::ApproxComponents), including floats, vectors, quaternions,
+/// colors, bases and transforms. Tolerance defaults to [`DEFAULT_EPSILON`](crate::assertions::DEFAULT_EPSILON) and can be set with
+/// `epsilon = value`. On failure, every differing component is listed.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_rehearse::itest::*;
+///
+/// #[gditest]
+/// fn rotated() {
+///     let rotated = Vector3::RIGHT.rotated(Vector3::UP, std::f32::consts::FRAC_PI_2);
+///     assert_approx_eq!(rotated, Vector3::FORWARD);
+///     assert_approx_eq!(rotated, Vector3::FORWARD, epsilon = 0.01, "rotated: {rotated}");
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assertions::approx_eq_impl(&$left, &$right, $crate::assertions::DEFAULT_EPSILON, None)
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr $(,)?) => {
+        $crate::assertions::approx_eq_impl(&$left, &$right, $epsilon as f64, None)
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr, $($arg:tt)+) => {
+        $crate::assertions::approx_eq_impl(&$left, &$right, $epsilon as f64, Some(format!($($arg)+)))
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::assertions::approx_eq_impl(
+            &$left,
+            &$right,
+            $crate::assertions::DEFAULT_EPSILON,
+            Some(format!($($arg)+)),
+        )
+    };
+}
+
+/// Asserts that a `Dictionary` contains all the entries of the expected one.
+///
+/// Expected entries can be provided as a `Dictionary` expression, or in braces with the `vdict!` syntax. On failure, every missing key
+/// and differing value is listed.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_rehearse::itest::*;
+///
+/// #[gditest]
+/// fn player_saved() {
+///     let saved = vdict! { "hp": 10, "name": "Player", "level": 2 };
+///     assert_dict_contains!(saved, { "hp": 10, "name": "Player" });
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_dict_contains {
+    ($dict:expr, { $($entries:tt)* } $(,)?) => {
+        $crate::assertions::dict_contains_impl(&$dict, &::godot::builtin::vdict! { $($entries)* }, None)
+    };
+    ($dict:expr, { $($entries:tt)* }, $($arg:tt)+) => {
+        $crate::assertions::dict_contains_impl(
+            &$dict,
+            &::godot::builtin::vdict! { $($entries)* },
+            Some(format!($($arg)+)),
+        )
+    };
+    ($dict:expr, $expected:expr $(,)?) => {
+        $crate::assertions::dict_contains_impl(&$dict, &$expected, None)
+    };
+    ($dict:expr, $expected:expr, $($arg:tt)+) => {
+        $crate::assertions::dict_contains_impl(&$dict, &$expected, Some(format!($($arg)+)))
+    };
+}
+
+/// Soft assertion: records a failure in [`TestContext`](crate::cases::rust_test_case::TestContext) if the condition is false, without
+/// stopping the test.
+///
+/// All failures are listed with their locations after the test finishes, which then is marked as failed.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_rehearse::itest::*;
+///
+/// #[gditest]
+/// fn player_spawned(ctx: &TestContext) {
+///     let stats = vdict! { "hp": 10, "level": 1 };
+///     expect!(ctx, stats.contains_key("hp"));
+///     expect!(ctx, stats.len() == 2, "unexpected stats: {stats}");
+///     expect_eq!(ctx, stats.get("level"), Some(1.to_variant()));
+/// }
+/// ```
+#[macro_export]
+macro_rules! expect {
+    ($ctx:expr, $condition:expr $(,)?) => {
+        if !$condition {
+            $ctx.record_check_failure(format!("`{}` is false", ::std::stringify!($condition)))
+        }
+    };
+    ($ctx:expr, $condition:expr, $($arg:tt)+) => {
+        if !$condition {
+            $ctx.record_check_failure(format!($($arg)+))
+        }
+    };
+}
+
+/// Soft assertion: records a failure in [`TestContext`](crate::cases::rust_test_case::TestContext) if two values are not equal, without
+/// stopping the test.
+///
+/// See [`expect!`](crate::expect) for details.
+#[macro_export]
+macro_rules! expect_eq {
+    ($ctx:expr, $left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    $ctx.record_check_failure(format!(
+                        "`left == right` failed\n  left: {left:?}\n right: {right:?}"
+                    ))
+                }
+            }
+        }
+    };
+    ($ctx:expr, $left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    $ctx.record_check_failure(format!(
+                        "`left == right` failed: {}\n  left: {left:?}\n right: {right:?}",
+                        format_args!($($arg)+)
+                    ))
+                }
+            }
+        }
+    };
+}
+
+/// Types which can be compared with tolerance by [`assert_approx_eq!`](crate::assert_approx_eq).
+pub trait ApproxComponents {
+    /// Named floating-point components of the value.
+    fn approx_components(&self) -> Vec<(String, f64)>;
+}
+
+impl ApproxComponents for f32 {
+    fn approx_components(&self) -> Vec<(String, f64)> {
+        vec![("value".to_owned(), *self as f64)]
+    }
+}
+
+impl ApproxComponents for f64 {
+    fn approx_components(&self) -> Vec<(String, f64)> {
+        vec![("value".to_owned(), *self)]
+    }
+}
+
+macro_rules! impl_approx_components {
+    ($ty:ty, $($field:ident),+) => {
+        impl ApproxComponents for $ty {
+            fn approx_components(&self) -> Vec<(String, f64)> {
+                vec![$((stringify!($field).to_owned(), self.$field as f64)),+]
+            }
+        }
+    };
+}
+
+impl_approx_components!(Vector2, x, y);
+impl_approx_components!(Vector3, x, y, z);
+impl_approx_components!(Vector4, x, y, z, w);
+impl_approx_components!(Quaternion, x, y, z, w);
+impl_approx_components!(Color, r, g, b, a);
+
+impl ApproxComponents for Basis {
+    fn approx_components(&self) -> Vec<(String, f64)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| prefixed(&format!("rows[{i}]"), row))
+            .collect()
+    }
+}
+
+impl ApproxComponents for Transform2D {
+    fn approx_components(&self) -> Vec<(String, f64)> {
+        let mut components = prefixed("a", &self.a);
+        components.extend(prefixed("b", &self.b));
+        components.extend(prefixed("origin", &self.origin));
+        components
+    }
+}
+
+impl ApproxComponents for Transform3D {
+    fn approx_components(&self) -> Vec<(String, f64)> {
+        let mut components = prefixed("basis", &self.basis);
+        components.extend(prefixed("origin", &self.origin));
+        components
+    }
+}
+
+fn prefixed(prefix: &str, value: &impl ApproxComponents) -> Vec<(String, f64)> {
+    value
+        .approx_components()
+        .into_iter()
+        .map(|(name, value)| (format!("{prefix}.{name}"), value))
+        .collect()
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn variant_eq_impl(left: &Variant, right: &Variant, message: Option<String>) {
+    let mut differences = Vec::new();
+    diff_variants("value", left, right, &mut differences);
+    if differences.is_empty() {
+        return;
+    }
+    fail(
+        "assertion `left == right` failed",
+        message,
+        &format!("{left:?}"),
+        &format!("{right:?}"),
+        &differences,
+    );
+}
+
+#[doc(hidden)]
+#[track_caller]
+// Negated comparison fails the assertion for NaN components too.
+#[allow(clippy::neg_cmp_op_on_partial_ord)]
+pub fn approx_eq_impl<T: ApproxComponents + std::fmt::Debug>(
+    left: &T,
+    right: &T,
+    epsilon: f64,
+    message: Option<String>,
+) {
+    let differences = left
+        .approx_components()
+        .into_iter()
+        .zip(right.approx_components())
+        .filter(|((_, left), (_, right))| !((left - right).abs() <= epsilon))
+        .map(|((name, left), (_, right))| {
+            format!(
+                "{name}: {left} != {right} (difference: {})",
+                (left - right).abs()
+            )
+        })
+        .collect::<Vec<_>>();
+    if differences.is_empty() {
+        return;
+    }
+    fail(
+        &format!("assertion `left ≈ right` failed (epsilon: {epsilon})"),
+        message,
+        &format!("{left:?}"),
+        &format!("{right:?}"),
+        &differences,
+    );
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn dict_contains_impl(dict: &Dictionary, expected: &Dictionary, message: Option<String>) {
+    let mut differences = Vec::new();
+    for (key, expected_value) in expected.iter_shared() {
+        let path = format!("[{key:?}]");
+        match dict.get(key) {
+            Some(value) => diff_variants(&path, &value, &expected_value, &mut differences),
+            None => differences.push(format!("{path}: missing, expected {expected_value:?}")),
+        }
+    }
+    if differences.is_empty() {
+        return;
+    }
+    fail(
+        "assertion `dict contains expected` failed",
+        message,
+        &format!("{dict:?}"),
+        &format!("{expected:?}"),
+        &differences,
+    );
+}
+
+fn diff_variants(path: &str, left: &Variant, right: &Variant, differences: &mut Vec<String>) {
+    if left == right {
+        return;
+    }
+
+    match (left.get_type(), right.get_type()) {
+        (VariantType::DICTIONARY, VariantType::DICTIONARY) => {
+            if let (Ok(left), Ok(right)) =
+                (left.try_to::<Dictionary>(), right.try_to::<Dictionary>())
+            {
+                diff_dictionaries(path, &left, &right, differences);
+                return;
+            }
+        }
+        (VariantType::ARRAY, VariantType::ARRAY) => {
+            if let (Ok(left), Ok(right)) = (
+                left.try_to::<VariantArray>(),
+                right.try_to::<VariantArray>(),
+            ) {
+                diff_arrays(path, &left, &right, differences);
+                return;
+            }
+        }
+        (left_type, right_type) if left_type != right_type => {
+            differences.push(format!(
+                "{path}: {left:?} ({left_type:?}) != {right:?} ({right_type:?})"
+            ));
+            return;
+        }
+        _ => {}
+    }
+    differences.push(format!("{path}: {left:?} != {right:?}"));
+}
+
+fn diff_dictionaries(
+    path: &str,
+    left: &Dictionary,
+    right: &Dictionary,
+    differences: &mut Vec<String>,
+) {
+    for (key, left_value) in left.iter_shared() {
+        let key_path = format!("{path}[{key:?}]");
+        match right.get(key) {
+            Some(right_value) => diff_variants(&key_path, &left_value, &right_value, differences),
+            None => differences.push(format!("{key_path}: only in left, {left_value:?}")),
+        }
+    }
+    for (key, right_value) in right.iter_shared() {
+        if !left.contains_key(key.clone()) {
+            differences.push(format!("{path}[{key:?}]: only in right, {right_value:?}"));
+        }
+    }
+}
+
+fn diff_arrays(
+    path: &str,
+    left: &VariantArray,
+    right: &VariantArray,
+    differences: &mut Vec<String>,
+) {
+    if left.len() != right.len() {
+        differences.push(format!("{path}: length {} != {}", left.len(), right.len()));
+    }
+    for i in 0..left.len().max(right.len()) {
+        let index_path = format!("{path}[{i}]");
+        match (left.get(i), right.get(i)) {
+            (Some(left), Some(right)) => diff_variants(&index_path, &left, &right, differences),
+            (Some(left), None) => differences.push(format!("{index_path}: only in left, {left:?}")),
+            (None, Some(right)) => {
+                differences.push(format!("{index_path}: only in right, {right:?}"))
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+#[track_caller]
+fn fail(assertion: &str, message: Option<String>, left: &str, right: &str, differences: &[String]) {
+    let mut out = assertion.to_owned();
+    if let Some(message) = message {
+        out.push_str(&format!(": {message}"));
+    }
+    out.push_str(&format!(
+        "\n   left: {left}\n  right: {right}\n  differences:"
+    ));
+    for difference in differences {
+        out.push_str(&format!("\n    {difference}"));
+    }
+    panic!("{out}");
+}
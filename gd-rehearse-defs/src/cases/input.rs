@@ -0,0 +1,192 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+use std::cell::RefCell;
+
+use godot::builtin::{StringName, Vector2};
+use godot::classes::{
+    Input, InputEvent, InputEventAction, InputEventJoypadMotion, InputEventKey,
+    InputEventMouseButton,
+};
+use godot::global::{JoyAxis, Key, MouseButton};
+use godot::obj::{Gd, Inherits, NewGd};
+
+/// Simulates input during `#[gditest]` tests.
+///
+/// Accessible with [TestContext::input](crate::cases::rust_test_case::TestContext::input). Events are injected with
+/// `Input::parse_input_event()` and buffered events are flushed right away, so `_input` and `_unhandled_input` handlers of nodes in the
+/// scene tree run before the method returns - the test doesn't need to wait for the next frame.
+///
+/// Actions, keys and mouse buttons which are still held after the test are released automatically, and moved joypad axes are returned
+/// to `0.0`.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use godot::classes::Input;
+/// use godot::global::Key;
+/// use gd_rehearse::itest::*;
+///
+/// #[gditest]
+/// fn jump_is_pressed(ctx: &TestContext) {
+///    ctx.input().press_action("jump");
+///    assert!(Input::singleton().is_action_pressed("jump"));
+///
+///    ctx.input().press_key(Key::SPACE);
+/// }
+/// ```
+#[derive(Default)]
+pub struct InputSimulator {
+    held: RefCell<HeldInputs>,
+}
+
+#[derive(Default)]
+struct HeldInputs {
+    actions: Vec<StringName>,
+    keys: Vec<Key>,
+    mouse_buttons: Vec<(MouseButton, Vector2)>,
+    joypad_axes: Vec<(i32, JoyAxis)>,
+}
+
+impl InputSimulator {
+    /// Presses the `action` with full strength.
+    pub fn press_action(&self, action: impl Into<StringName>) {
+        self.press_action_with_strength(action, 1.0);
+    }
+
+    /// Presses the `action` with given `strength`, in range `0.0..=1.0`.
+    pub fn press_action_with_strength(&self, action: impl Into<StringName>, strength: f32) {
+        let action: StringName = action.into();
+        self.send_action(&action, true, strength);
+
+        let mut held = self.held.borrow_mut();
+        if !held.actions.contains(&action) {
+            held.actions.push(action);
+        }
+    }
+
+    pub fn release_action(&self, action: impl Into<StringName>) {
+        let action: StringName = action.into();
+        self.send_action(&action, false, 0.0);
+        self.held
+            .borrow_mut()
+            .actions
+            .retain(|held| held != &action);
+    }
+
+    pub fn press_key(&self, key: Key) {
+        self.send_key(key, true);
+
+        let mut held = self.held.borrow_mut();
+        if !held.keys.contains(&key) {
+            held.keys.push(key);
+        }
+    }
+
+    pub fn release_key(&self, key: Key) {
+        self.send_key(key, false);
+        self.held.borrow_mut().keys.retain(|held| held != &key);
+    }
+
+    /// Presses and releases the `key`.
+    pub fn tap_key(&self, key: Key) {
+        self.press_key(key);
+        self.release_key(key);
+    }
+
+    /// Presses the mouse `button` at `position`, in viewport coordinates.
+    pub fn press_mouse_button(&self, button: MouseButton, position: Vector2) {
+        self.send_mouse_button(button, position, true);
+
+        let mut held = self.held.borrow_mut();
+        held.mouse_buttons.retain(|(held, _)| held != &button);
+        held.mouse_buttons.push((button, position));
+    }
+
+    /// Releases the mouse `button` at `position`, in viewport coordinates.
+    pub fn release_mouse_button(&self, button: MouseButton, position: Vector2) {
+        self.send_mouse_button(button, position, false);
+        self.held
+            .borrow_mut()
+            .mouse_buttons
+            .retain(|(held, _)| held != &button);
+    }
+
+    /// Presses and releases the mouse `button` at `position`, in viewport coordinates.
+    pub fn click_mouse_button(&self, button: MouseButton, position: Vector2) {
+        self.press_mouse_button(button, position);
+        self.release_mouse_button(button, position);
+    }
+
+    /// Moves the joypad `axis` of `device` to `value`, in range `-1.0..=1.0`.
+    pub fn move_joypad_axis(&self, device: i32, axis: JoyAxis, value: f32) {
+        self.send_joypad_axis(device, axis, value);
+
+        let mut held = self.held.borrow_mut();
+        held.joypad_axes.retain(|held| held != &(device, axis));
+        if value != 0.0 {
+            held.joypad_axes.push((device, axis));
+        }
+    }
+
+    /// Injects any custom input event.
+    pub fn parse_event<T: Inherits<InputEvent>>(&self, event: Gd<T>) {
+        let mut input = Input::singleton();
+        input.parse_input_event(&event.upcast::<InputEvent>());
+        input.flush_buffered_events();
+    }
+
+    /// Releases all actions, keys and mouse buttons which are still held, and returns moved joypad axes to `0.0`.
+    pub(crate) fn release_all(&self) {
+        let held = std::mem::take(&mut *self.held.borrow_mut());
+        for action in held.actions.iter() {
+            self.send_action(action, false, 0.0);
+        }
+        for key in held.keys {
+            self.send_key(key, false);
+        }
+        for (button, position) in held.mouse_buttons {
+            self.send_mouse_button(button, position, false);
+        }
+        for (device, axis) in held.joypad_axes {
+            self.send_joypad_axis(device, axis, 0.0);
+        }
+    }
+
+    fn send_action(&self, action: &StringName, pressed: bool, strength: f32) {
+        let mut event = InputEventAction::new_gd();
+        event.set_action(action);
+        event.set_pressed(pressed);
+        event.set_strength(strength);
+        self.parse_event(event);
+    }
+
+    fn send_key(&self, key: Key, pressed: bool) {
+        let mut event = InputEventKey::new_gd();
+        event.set_keycode(key);
+        event.set_physical_keycode(key);
+        event.set_pressed(pressed);
+        self.parse_event(event);
+    }
+
+    fn send_joypad_axis(&self, device: i32, axis: JoyAxis, value: f32) {
+        let mut event = InputEventJoypadMotion::new_gd();
+        event.set_device(device);
+        event.set_axis(axis);
+        event.set_axis_value(value);
+        self.parse_event(event);
+    }
+
+    fn send_mouse_button(&self, button: MouseButton, position: Vector2, pressed: bool) {
+        let mut event = InputEventMouseButton::new_gd();
+        event.set_button_index(button);
+        event.set_position(position);
+        event.set_global_position(position);
+        event.set_pressed(pressed);
+        self.parse_event(event);
+    }
+}
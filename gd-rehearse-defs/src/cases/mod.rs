@@ -5,15 +5,21 @@
 */
 
 pub mod gd_test_case;
+pub mod input;
 pub mod rust_bench;
 pub mod rust_test_case;
+pub mod rust_test_suite;
+pub mod signal_spy;
 
 use std::cmp::Ordering;
 
-use godot::builtin::NodePath;
-use godot::classes::{Engine, Node};
+use godot::builtin::{GString, NodePath};
+use godot::classes::{Engine, Node, PackedScene};
 use godot::meta::AsArg;
 use godot::obj::{Gd, Inherits};
+use godot::tools::try_load;
+
+use crate::runner::config::RunnerInfo;
 
 // /// Optional test context for `#[gditest]` and `#[gdbench]` annotated functions.
 // ///
@@ -61,6 +67,21 @@ pub trait CaseContext {
     }
 }
 
+/// Loads `PackedScene` from `path` and adds its instance as a child of `parent`.
+///
+/// # Panics
+///
+/// Panics if the scene cannot be loaded or instantiated.
+pub(crate) fn instantiate_scene_under(parent: &Gd<Node>, path: &GString) -> Gd<Node> {
+    let packed = try_load::<PackedScene>(path)
+        .unwrap_or_else(|err| panic!("cannot load scene `{path}`: {err}"));
+    let instance = packed
+        .instantiate()
+        .unwrap_or_else(|| panic!("cannot instantiate scene `{path}`"));
+    parent.clone().add_child(&instance);
+    instance
+}
+
 /// Case outcome.
 #[derive(PartialEq)]
 #[must_use]
@@ -68,6 +89,12 @@ pub(crate) enum CaseOutcome {
     Passed,
     Failed,
     Skipped,
+    /// Case marked with `xfail` failed, as expected.
+    ExpectedFailure,
+    /// Case marked with `xfail` passed. Treated as failure.
+    UnexpectedPass,
+    /// Case passed, but only after being retried.
+    Flaky,
 }
 
 impl CaseOutcome {
@@ -87,11 +114,59 @@ impl std::fmt::Display for CaseOutcome {
             CaseOutcome::Passed => "ok!",
             CaseOutcome::Failed => "FAILED",
             CaseOutcome::Skipped => "~skipped~",
+            CaseOutcome::ExpectedFailure => "xfail",
+            CaseOutcome::UnexpectedPass => "UNEXPECTED PASS",
+            CaseOutcome::Flaky => "flaky",
         };
         f.write_str(outcome)
     }
 }
 
+/// Condition under which the case is skipped, set with `skip_if` attribute.
+#[derive(Clone, Copy)]
+pub enum SkipCondition {
+    Headless,
+    Editor,
+    GodotRelease,
+    GodotDebug,
+    RustRelease,
+    RustDebug,
+    /// User-provided function. Case is skipped if it returns `true`.
+    Custom {
+        name: &'static str,
+        condition: fn(&RunnerInfo) -> bool,
+    },
+}
+
+impl SkipCondition {
+    pub(crate) fn is_met(&self, info: &RunnerInfo) -> bool {
+        match self {
+            SkipCondition::Headless => info.is_headless(),
+            SkipCondition::Editor => !info.is_headless(),
+            SkipCondition::GodotRelease => !info.is_godot_debug(),
+            SkipCondition::GodotDebug => info.is_godot_debug(),
+            SkipCondition::RustRelease => !info.is_rust_debug(),
+            SkipCondition::RustDebug => info.is_rust_debug(),
+            SkipCondition::Custom { condition, .. } => (condition)(info),
+        }
+    }
+}
+
+impl std::fmt::Display for SkipCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let condition = match self {
+            SkipCondition::Headless => "headless",
+            SkipCondition::Editor => "editor",
+            SkipCondition::GodotRelease => "godot_release",
+            SkipCondition::GodotDebug => "godot_debug",
+            SkipCondition::RustRelease => "rust_release",
+            SkipCondition::RustDebug => "rust_debug",
+            SkipCondition::Custom { name, .. } => name,
+        };
+        write!(f, "skip_if: {condition}")
+    }
+}
+
 /// Disable printing errors from Godot. Ideally we should catch and handle errors, ensuring they happen when
 /// expected. But that isn't possible, so for now we can just disable printing the error to avoid spamming
 /// the terminal when tests should error.
@@ -104,26 +179,53 @@ pub fn suppress_godot_print(mut f: impl FnMut()) {
 pub(crate) trait Case {
     fn is_case_focus(&self) -> bool;
     fn is_case_skip(&self) -> bool;
+    fn get_case_skip_reason(&self) -> &Option<&str>;
+    fn get_case_skip_condition(&self) -> &Option<SkipCondition>;
     fn get_case_keyword(&self) -> &Option<&str>;
+    fn get_case_tags(&self) -> &[&str];
     fn get_case_name(&self) -> &str;
     fn get_case_line(&self) -> u32;
     fn get_case_file(&self) -> &str;
+    fn get_case_module_path(&self) -> &str;
     fn get_case_scene_path(&self) -> &Option<&str>;
 
+    fn get_case_qualified_name(&self) -> String {
+        format!("{}::{}", self.get_case_module_path(), self.get_case_name())
+    }
+
     fn order(first: &Self, other: &Self) -> Ordering {
         other.get_order_string().cmp(&first.get_order_string())
     }
 
+    // Cases are grouped by module, and inside it ordered by their declaration. Qualified name is used as a tiebreaker.
     fn get_order_string(&self) -> String {
-        format!("{}{:06}", self.get_case_file(), self.get_case_line())
+        format!(
+            "{} {} {:06} {}",
+            self.get_case_module_path(),
+            self.get_case_file(),
+            self.get_case_line(),
+            self.get_case_qualified_name()
+        )
     }
 
     fn should_run_focus(&self, is_focus_run: bool) -> bool {
         !is_focus_run || self.is_case_focus()
     }
 
-    fn should_run_skip(&self, disallow_skip: bool) -> bool {
-        !self.is_case_skip() || disallow_skip
+    // Conditional skip reflects the environment the case can't run in, so it isn't affected by `disallow_skip`.
+    fn should_run_skip(&self, disallow_skip: bool, info: &RunnerInfo) -> bool {
+        let skip_met = self
+            .get_case_skip_condition()
+            .is_some_and(|condition| condition.is_met(info));
+        (!self.is_case_skip() || disallow_skip) && !skip_met
+    }
+
+    fn get_skip_message(&self) -> Option<String> {
+        if self.is_case_skip() {
+            return self.get_case_skip_reason().map(|reason| reason.to_owned());
+        }
+        self.get_case_skip_condition()
+            .map(|condition| condition.to_string())
     }
 
     fn should_run_keyword(&self, keyword: &str, ignore_keywords: bool) -> bool {
@@ -138,13 +240,36 @@ pub(crate) trait Case {
         false
     }
 
+    fn should_run_tags(
+        &self,
+        tags: &[String],
+        exclude_tags: &[String],
+        match_all_tags: bool,
+    ) -> bool {
+        let case_tags = self.get_case_tags();
+        if exclude_tags
+            .iter()
+            .any(|tag| case_tags.contains(&tag.as_str()))
+        {
+            return false;
+        }
+        if tags.is_empty() {
+            return true;
+        }
+        let mut matching = tags.iter().map(|tag| case_tags.contains(&tag.as_str()));
+        if match_all_tags {
+            matching.all(|matches| matches)
+        } else {
+            matching.any(|matches| matches)
+        }
+    }
+
     fn should_run_filters(&self, filters: &[String]) -> bool {
         if filters.is_empty() {
             return true;
         };
-        filters
-            .iter()
-            .any(|filter| self.get_case_name().contains(filter))
+        let qualified_name = self.get_case_qualified_name();
+        filters.iter().any(|filter| qualified_name.contains(filter))
     }
 
     fn should_run_scene_path(&self, scene_path: &str, is_path_run: bool) -> bool {
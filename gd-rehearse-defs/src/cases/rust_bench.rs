@@ -8,7 +8,7 @@ use std::cell::RefCell;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::Display;
-use std::panic::RefUnwindSafe;
+use std::panic::{AssertUnwindSafe, RefUnwindSafe};
 use std::time::{Duration, Instant};
 
 use godot::builtin::{GString, NodePath};
@@ -16,9 +16,10 @@ use godot::classes::Node;
 use godot::meta::AsArg;
 use godot::obj::{Gd, Inherits};
 
-use crate::runner::panic::{unwind_result, UnwindError, UnwindResult};
+use crate::runner::isolation::StrayNodesError;
+use crate::runner::panic::{handle_panic, unwind_result, UnwindError, UnwindResult};
 
-use super::{Case, CaseContext};
+use super::{instantiate_scene_under, Case, CaseContext, SkipCondition};
 
 /// Rust benchmark.
 ///
@@ -27,10 +28,20 @@ use super::{Case, CaseContext};
 pub struct RustBenchmark {
     pub name: &'static str,
     pub file: &'static str,
+    /// Module in which the benchmark was declared, as returned by `module_path!()`.
+    pub module_path: &'static str,
     pub skipped: bool,
+    /// Reason of the skip, printed next to the skipped benchmark.
+    pub skip_reason: Option<&'static str>,
+    /// Condition evaluated during run, which causes the benchmark to be skipped if met.
+    pub skip_condition: Option<SkipCondition>,
     pub focused: bool,
     pub keyword: Option<&'static str>,
+    /// Tags used for selecting the benchmark with runner `tags` and `exclude_tags` options.
+    pub tags: &'static [&'static str],
     pub scene_path: Option<&'static str>,
+    /// Scene instantiated under the runner before the benchmark, and freed after it.
+    pub scene: Option<&'static str>,
     #[allow(dead_code)]
     pub line: u32,
     pub function: fn(&BenchContext),
@@ -46,15 +57,27 @@ impl Case for RustBenchmark {
     fn get_case_file(&self) -> &str {
         self.file
     }
+    fn get_case_module_path(&self) -> &str {
+        self.module_path
+    }
     fn is_case_focus(&self) -> bool {
         self.focused
     }
     fn is_case_skip(&self) -> bool {
         self.skipped
     }
+    fn get_case_skip_reason(&self) -> &Option<&str> {
+        &self.skip_reason
+    }
+    fn get_case_skip_condition(&self) -> &Option<SkipCondition> {
+        &self.skip_condition
+    }
     fn get_case_keyword(&self) -> &Option<&str> {
         &self.keyword
     }
+    fn get_case_tags(&self) -> &[&str] {
+        self.tags
+    }
     fn get_case_scene_path(&self) -> &Option<&str> {
         &self.scene_path
     }
@@ -64,21 +87,38 @@ impl Case for RustBenchmark {
 }
 
 impl RustBenchmark {
+    /// Name of the benchmark prefixed with the path of the module it was declared in, eg. `my_crate::physics::bench_collision`.
+    pub fn qualified_name(&self) -> String {
+        self.get_case_qualified_name()
+    }
+
     pub(crate) fn execute_setup_function(
         &self,
         ctx: BenchContext,
     ) -> Result<BenchContext, UnwindError> {
-        if let Some(setup) = self.setup_function {
-            let mut cloned_ctx = ctx.clone();
+        if self.setup_function.is_none() && self.scene.is_none() {
+            return Ok(ctx);
+        }
+        let mut setup_ctx = ctx.clone();
+        let scene = self.scene;
+        let setup_function = self.setup_function;
 
-            let res: UnwindResult<BenchContext> = std::panic::catch_unwind(move || {
-                (setup)(&mut cloned_ctx);
-                Ok(cloned_ctx)
-            });
+        let result = handle_panic(AssertUnwindSafe(|| {
+            if let Some(scene) = scene {
+                setup_ctx.instantiate_case_scene(scene);
+            }
+            if let Some(setup) = setup_function {
+                (setup)(&mut setup_ctx);
+            }
+        }));
 
-            return unwind_result(res);
+        // Otherwise the scene and nodes added before the panic would stay under the runner during the next cases.
+        if let Err(err) = result {
+            setup_ctx.free_instantiated_scenes();
+            setup_ctx.remove_all_added_nodes();
+            return Err(err);
         }
-        Ok(ctx)
+        Ok(setup_ctx)
     }
 
     pub(crate) fn execute_cleanup_function(
@@ -150,6 +190,8 @@ pub struct BenchContext {
     pub(crate) scene_tree: Gd<Node>,
     added_nodes: HashSet<GString>,
     sub_durations: RefCell<Duration>,
+    case_scene: Option<Gd<Node>>,
+    instantiated_scenes: RefCell<Vec<Gd<Node>>>,
 }
 
 impl CaseContext for BenchContext {
@@ -188,6 +230,75 @@ impl BenchContext {
             scene_tree,
             added_nodes: HashSet::new(),
             sub_durations: RefCell::new(Duration::default()),
+            case_scene: None,
+            instantiated_scenes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Loads `PackedScene` from `path` and adds its instance under the [GdTestRunner](crate::runner::GdTestRunner). The instance is freed
+    /// automatically after the benchmark.
+    ///
+    /// Time of loading and instantiating the scene is not adjusted for - if it isn't the benchmarked operation, instantiate the scene
+    /// in the setup function or with `scene` attribute.
+    ///
+    /// ## Panics
+    ///
+    /// If the scene cannot be loaded, or its root cannot be casted to `T`.
+    pub fn instantiate_scene<T: Inherits<Node>>(&self, path: impl Into<GString>) -> Gd<T> {
+        let instance = instantiate_scene_under(&self.scene_tree, &path.into());
+        self.instantiated_scenes.borrow_mut().push(instance.clone());
+        instance
+            .try_cast::<T>()
+            .unwrap_or_else(|instance| panic!("cannot cast scene root `{}`", instance.get_name()))
+    }
+
+    /// Gets root of the scene instantiated for the benchmark with `scene` attribute.
+    ///
+    /// ## Panics
+    ///
+    /// If the benchmark has no `scene` attribute.
+    pub fn scene(&self) -> Gd<Node> {
+        let start = Instant::now();
+        let out = self
+            .case_scene
+            .clone()
+            .expect("no scene was instantiated with `scene` attribute");
+        *self.sub_durations.borrow_mut() += start.elapsed();
+        out
+    }
+
+    /// Gets root of the scene instantiated for the benchmark with `scene` attribute, casted to `T`.
+    ///
+    /// ## Panics
+    ///
+    /// If the benchmark has no `scene` attribute, or scene root cannot be casted to `T`.
+    pub fn scene_as<T: Inherits<Node>>(&self) -> Gd<T> {
+        let start = Instant::now();
+        let out = self
+            .case_scene
+            .clone()
+            .expect("no scene was instantiated with `scene` attribute")
+            .try_cast::<T>()
+            .expect("cannot cast scene root");
+        *self.sub_durations.borrow_mut() += start.elapsed();
+        out
+    }
+
+    pub(crate) fn instantiate_case_scene(&mut self, path: &str) {
+        self.case_scene = Some(self.instantiate_scene::<Node>(path));
+    }
+
+    /// Frees all scenes instantiated with [BenchContext::instantiate_scene] and `scene` attribute.
+    pub(crate) fn free_instantiated_scenes(&mut self) {
+        self.case_scene = None;
+        for mut instance in self.instantiated_scenes.get_mut().drain(..) {
+            if instance.is_instance_valid() {
+                // Detach right away, so the instance won't collide with the next case nodes before being freed.
+                if let Some(mut parent) = instance.get_parent() {
+                    parent.remove_child(&instance);
+                }
+                instance.queue_free();
+            }
         }
     }
 
@@ -283,6 +394,8 @@ pub(crate) enum BenchError {
     Setup(UnwindError),
     Execution(UnwindError),
     Cleanup(CleanupError),
+    Isolation(StrayNodesError),
+    Multiple(Vec<BenchError>),
 }
 
 impl Display for BenchError {
@@ -291,6 +404,8 @@ impl Display for BenchError {
             BenchError::Setup(err) => write!(f, "[setup] {err}"),
             BenchError::Execution(err) => write!(f, "[execution] {err}"),
             BenchError::Cleanup(err) => write!(f, "[cleanup] {err}"),
+            BenchError::Isolation(err) => write!(f, "[isolation] {err}"),
+            BenchError::Multiple(errors) => super::rust_test_case::write_multiple(f, errors),
         }
     }
 }
@@ -4,10 +4,24 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
 */
 
-use godot::classes::Node;
-use godot::obj::Gd;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::panic::{AssertUnwindSafe, Location};
 
-use super::{Case, CaseContext};
+use godot::builtin::{GString, NodePath, StringName};
+use godot::classes::{Engine, Node, Object};
+use godot::meta::AsArg;
+use godot::obj::{Gd, Inherits};
+
+use crate::property::{check_property, default_iterations, Generator};
+use crate::runner::isolation::StrayNodesError;
+use crate::runner::panic::{handle_panic, UnwindError};
+
+use super::input::InputSimulator;
+use super::signal_spy::SignalSpy;
+use super::{instantiate_scene_under, Case, CaseContext, SkipCondition};
 
 /// Rust test case.
 ///
@@ -16,15 +30,31 @@ use super::{Case, CaseContext};
 pub struct RustTestCase {
     pub name: &'static str,
     pub file: &'static str,
+    /// Module in which the test was declared, as returned by `module_path!()`.
+    pub module_path: &'static str,
     pub skipped: bool,
+    /// Reason of the skip, printed next to the skipped test.
+    pub skip_reason: Option<&'static str>,
+    /// Condition evaluated during run, which causes the test to be skipped if met.
+    pub skip_condition: Option<SkipCondition>,
     /// If one or more tests are focused, only they will be executed. Helpful for debugging and working on specific features.
     pub focused: bool,
     /// Used in conjuction with set
     pub keyword: Option<&'static str>,
+    /// Tags used for selecting the test with runner `tags` and `exclude_tags` options.
+    pub tags: &'static [&'static str],
     pub scene_path: Option<&'static str>,
+    /// Scene instantiated under the runner before the test, and freed after it.
+    pub scene: Option<&'static str>,
+    /// If set, test is expected to fail for given reason. Passing of such test is treated as failure.
+    pub xfail: Option<&'static str>,
+    /// How many times failing test should be rerun before it's considered failed.
+    pub retries: u32,
     #[allow(dead_code)]
     pub line: u32,
     pub function: fn(&TestContext),
+    pub setup_function: Option<fn(&mut TestContext)>,
+    pub teardown_function: Option<fn(&mut TestContext)>,
 }
 
 impl Case for RustTestCase {
@@ -34,15 +64,27 @@ impl Case for RustTestCase {
     fn get_case_file(&self) -> &str {
         self.file
     }
+    fn get_case_module_path(&self) -> &str {
+        self.module_path
+    }
     fn is_case_focus(&self) -> bool {
         self.focused
     }
     fn is_case_skip(&self) -> bool {
         self.skipped
     }
+    fn get_case_skip_reason(&self) -> &Option<&str> {
+        &self.skip_reason
+    }
+    fn get_case_skip_condition(&self) -> &Option<SkipCondition> {
+        &self.skip_condition
+    }
     fn get_case_keyword(&self) -> &Option<&str> {
         &self.keyword
     }
+    fn get_case_tags(&self) -> &[&str] {
+        self.tags
+    }
     fn get_case_scene_path(&self) -> &Option<&str> {
         &self.scene_path
     }
@@ -51,16 +93,524 @@ impl Case for RustTestCase {
     }
 }
 
+impl RustTestCase {
+    /// Name of the test prefixed with the path of the module it was declared in, eg. `my_crate::physics::setup_works`.
+    pub fn qualified_name(&self) -> String {
+        self.get_case_qualified_name()
+    }
+
+    /// Executes setup function and test function. If the test function scheduled physics ticks to advance, the test needs to be resumed
+    /// after them. Test needs to be finished with [RustTestCase::finish] afterwards.
+    pub(crate) fn start(&self, ctx: &mut TestContext) -> Result<(), TestError> {
+        let setup_result = handle_panic(AssertUnwindSafe(|| {
+            if let Some(scene) = self.scene {
+                ctx.instantiate_case_scene(scene);
+            }
+            if let Some(setup) = self.setup_function {
+                (setup)(ctx);
+            }
+        }));
+        if let Err(err) = setup_result {
+            // Remove nodes added before the panic, so they won't leak into next tests.
+            ctx.remove_all_added_nodes();
+            return Err(TestError::Setup(err));
+        }
+
+        ctx.can_advance.set(true);
+        let result =
+            handle_panic(AssertUnwindSafe(|| (self.function)(ctx))).map_err(TestError::Execution);
+        ctx.can_advance.set(false);
+        result
+    }
+
+    /// Executes continuation of the test function, scheduled with [TestContext::advance_physics_ticks].
+    pub(crate) fn resume(
+        &self,
+        ctx: &TestContext,
+        continuation: Box<dyn FnOnce(&TestContext)>,
+    ) -> Result<(), TestError> {
+        ctx.can_advance.set(true);
+        let result =
+            handle_panic(AssertUnwindSafe(|| (continuation)(ctx))).map_err(TestError::Execution);
+        ctx.can_advance.set(false);
+        result
+    }
+
+    /// Executes teardown function and restores the state changed by the test. Teardown is executed even if the test function panicked.
+    pub(crate) fn finish(
+        &self,
+        ctx: &mut TestContext,
+        result: Result<(), TestError>,
+    ) -> Result<(), TestError> {
+        // Dropping the continuation which won't be executed.
+        let _ = ctx.take_pending_ticks();
+        let result = match result {
+            Err(TestError::Setup(err)) => Err(TestError::Setup(err)),
+            result => result.and(self.execute_teardown_function(ctx)),
+        };
+        ctx.input.release_all();
+        ctx.restore_engine();
+        ctx.free_instantiated_scenes();
+
+        let failures = ctx.take_check_failures();
+        if failures.is_empty() {
+            return result;
+        }
+        Err(TestError::Checks {
+            failures,
+            error: result.err().map(Box::new),
+        })
+    }
+
+    fn execute_teardown_function(&self, ctx: &mut TestContext) -> Result<(), TestError> {
+        let Some(teardown) = self.teardown_function else {
+            ctx.remove_all_added_nodes();
+            return Ok(());
+        };
+
+        let result = handle_panic(AssertUnwindSafe(|| (teardown)(ctx)));
+        if ctx.has_added_nodes() {
+            ctx.remove_all_added_nodes();
+            if result.is_ok() {
+                return Err(TestError::NotCleanedUp);
+            }
+        }
+        result.map_err(TestError::Teardown)
+    }
+}
+
 /// Optional test context for `#[gditest]`.
 ///
-/// Allows accessing [GdTestRunner](crate::runner::GdTestRunner) scene tree during tests.
+/// Allows accessing [GdTestRunner](crate::runner::GdTestRunner) scene tree during tests. Nodes needed by the test can be prepared in
+/// `setup` function and retrieved during the test - they will be removed after the test, even if it panicked.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_rehearse::itest::*;
+///
+/// fn setup_function(ctx: &mut TestContext) {
+///    let node = Node::new_alloc();
+///    ctx.setup_add_node(node, "OnSetup");
+/// }
+///
+/// #[gditest(setup=setup_function)]
+/// fn with_setup(ctx: &TestContext) {
+///    let from_setup = ctx.get_setup_node("OnSetup");
+///    assert_eq!(from_setup.get_name(), "OnSetup".into());
+/// }
+/// ```
 pub struct TestContext {
     pub(crate) scene_tree: Gd<Node>,
+    suite_tree: Gd<Node>,
+    added_nodes: HashSet<GString>,
+    case_scene: Option<Gd<Node>>,
+    instantiated_scenes: RefCell<Vec<Gd<Node>>>,
+    input: InputSimulator,
+    can_advance: Cell<bool>,
+    pending_ticks: RefCell<Option<PendingTicks>>,
+    original_physics_ticks: Cell<Option<i32>>,
+    original_time_scale: Cell<Option<f64>>,
+    check_failures: RefCell<Vec<CheckFailure>>,
+}
+
+/// Continuation of the test function, executed after physics ticks.
+pub(crate) struct PendingTicks {
+    pub(crate) ticks: u32,
+    pub(crate) continuation: Box<dyn FnOnce(&TestContext)>,
 }
 
 impl TestContext {
     pub(crate) fn new(scene_tree: Gd<Node>) -> Self {
-        Self { scene_tree }
+        Self {
+            suite_tree: scene_tree.clone(),
+            scene_tree,
+            added_nodes: HashSet::new(),
+            case_scene: None,
+            instantiated_scenes: RefCell::new(Vec::new()),
+            input: InputSimulator::default(),
+            can_advance: Cell::new(false),
+            pending_ticks: RefCell::new(None),
+            original_physics_ticks: Cell::new(None),
+            original_time_scale: Cell::new(None),
+            check_failures: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Loads `PackedScene` from `path` and adds its instance under the [GdTestRunner](crate::runner::GdTestRunner). The instance is freed
+    /// automatically after the test.
+    ///
+    /// ## Panics
+    ///
+    /// If the scene cannot be loaded, or its root cannot be casted to `T`.
+    pub fn instantiate_scene<T: Inherits<Node>>(&self, path: impl Into<GString>) -> Gd<T> {
+        let instance = instantiate_scene_under(&self.scene_tree, &path.into());
+        self.instantiated_scenes.borrow_mut().push(instance.clone());
+        instance
+            .try_cast::<T>()
+            .unwrap_or_else(|instance| panic!("cannot cast scene root `{}`", instance.get_name()))
+    }
+
+    /// Gets root of the scene instantiated for the test with `scene` attribute.
+    ///
+    /// ## Panics
+    ///
+    /// If the test has no `scene` attribute.
+    pub fn scene(&self) -> Gd<Node> {
+        self.case_scene
+            .clone()
+            .expect("no scene was instantiated with `scene` attribute")
+    }
+
+    /// Gets root of the scene instantiated for the test with `scene` attribute, casted to `T`.
+    ///
+    /// ## Panics
+    ///
+    /// If the test has no `scene` attribute, or scene root cannot be casted to `T`.
+    pub fn scene_as<T: Inherits<Node>>(&self) -> Gd<T> {
+        self.scene()
+            .try_cast::<T>()
+            .expect("cannot cast scene root")
+    }
+
+    /// Gets input simulator, which injects input events for the test. Inputs still held after the test are released.
+    pub fn input(&self) -> &InputSimulator {
+        &self.input
+    }
+
+    /// Continues the test with `continuation` after exactly `ticks` physics ticks have passed.
+    ///
+    /// Test function cannot block to wait for the engine, so the runner suspends the test after the function returns, lets the engine
+    /// process the physics ticks and executes the `continuation` afterwards. Physics delta of every tick is fixed and can be adjusted with
+    /// [TestContext::set_physics_ticks_per_second]. Continuation can advance the ticks again, to check the state in more steps.
+    ///
+    /// ## Panics
+    ///
+    /// If called outside of test function or its continuation, or if ticks were already scheduled by it.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use godot::prelude::*;
+    /// use godot::classes::RigidBody2D;
+    /// use gd_rehearse::itest::*;
+    ///
+    /// #[gditest]
+    /// fn body_falls(ctx: &TestContext) {
+    ///    let mut body = RigidBody2D::new_alloc();
+    ///    ctx.scene_tree().clone().add_child(&body);
+    ///
+    ///    ctx.set_physics_ticks_per_second(60);
+    ///    ctx.advance_physics_ticks(10, move |_ctx| {
+    ///        assert!(body.get_position().y > 0.0);
+    ///        body.queue_free();
+    ///    });
+    /// }
+    /// ```
+    pub fn advance_physics_ticks(
+        &self,
+        ticks: u32,
+        continuation: impl FnOnce(&TestContext) + 'static,
+    ) {
+        if !self.can_advance.get() {
+            panic!("physics ticks can be advanced only in the test function or its continuation");
+        }
+        let mut pending = self.pending_ticks.borrow_mut();
+        if pending.is_some() {
+            panic!("physics ticks were already scheduled to advance");
+        }
+        *pending = Some(PendingTicks {
+            ticks,
+            continuation: Box::new(continuation),
+        });
+    }
+
+    /// Sets `Engine` physics ticks per second for the duration of the test. Original value is restored after the test.
+    pub fn set_physics_ticks_per_second(&self, ticks_per_second: i32) {
+        let mut engine = Engine::singleton();
+        if self.original_physics_ticks.get().is_none() {
+            self.original_physics_ticks
+                .set(Some(engine.get_physics_ticks_per_second()));
+        }
+        engine.set_physics_ticks_per_second(ticks_per_second);
+    }
+
+    /// Sets `Engine` time scale for the duration of the test. Original value is restored after the test.
+    pub fn set_time_scale(&self, time_scale: f64) {
+        let mut engine = Engine::singleton();
+        if self.original_time_scale.get().is_none() {
+            self.original_time_scale.set(Some(engine.get_time_scale()));
+        }
+        engine.set_time_scale(time_scale);
+    }
+
+    /// Soft assertion: records a failure with `message` if `condition` is false, without stopping the test. The test is marked as failed
+    /// after it finishes, listing every recorded failure with its location.
+    ///
+    /// See also [`expect!`](crate::expect) and [`expect_eq!`](crate::expect_eq) macros.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use godot::prelude::*;
+    /// use gd_rehearse::itest::*;
+    ///
+    /// #[gditest]
+    /// fn player_stats(ctx: &TestContext) {
+    ///    let stats = vdict! { "hp": 10, "mana": 5 };
+    ///    ctx.check(stats.contains_key("hp"), "missing `hp`");
+    ///    ctx.check_eq(stats.get("mana"), Some(5.to_variant()));
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn check(&self, condition: bool, message: impl Display) {
+        if !condition {
+            self.record_check_failure(message.to_string());
+        }
+    }
+
+    /// Soft assertion: records a failure if `left` and `right` are not equal, without stopping the test.
+    #[track_caller]
+    pub fn check_eq<T: PartialEq + Debug>(&self, left: T, right: T) {
+        if left != right {
+            self.record_check_failure(format!(
+                "`left == right` failed\n  left: {left:?}\n right: {right:?}"
+            ));
+        }
+    }
+
+    /// Number of failures recorded by soft assertions so far.
+    pub fn check_failures_count(&self) -> usize {
+        self.check_failures.borrow().len()
+    }
+
+    /// Takes the failures recorded by soft assertions so far, formatted as they would fail the test. Taken failures no longer fail it.
+    ///
+    /// Returns `None` if no failures were recorded.
+    pub fn take_check_failures_report(&self) -> Option<String> {
+        let failures = self.take_check_failures();
+        if failures.is_empty() {
+            return None;
+        }
+        Some(
+            TestError::Checks {
+                failures,
+                error: None,
+            }
+            .to_string(),
+        )
+    }
+
+    /// Checks that `property` holds for inputs generated by `generator`, which can be one of [generators](crate::property::generators),
+    /// tuple of them or custom implementation of [Generator].
+    ///
+    /// Property fails by panicking, eg. with an assertion. Failing input is shrunk to a minimal one, and the test panics with it and the
+    /// seed, which can be passed with `--prop-seed=N` to reproduce the failure. Number of iterations is 100, unless set with
+    /// `--prop-iterations=N`.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use godot::prelude::*;
+    /// use gd_rehearse::itest::*;
+    /// use gd_rehearse::itest::generators::*;
+    ///
+    /// #[gditest]
+    /// fn normalized_has_unit_length(ctx: &TestContext) {
+    ///    ctx.forall(vector2(-100.0..100.0), |vector| {
+    ///        if !vector.is_zero_approx() {
+    ///            assert_approx_eq!(vector.normalized().length(), 1.0, epsilon = 1e-4);
+    ///        }
+    ///    });
+    /// }
+    /// ```
+    pub fn forall<G: Generator>(&self, generator: G, property: impl Fn(G::Value)) {
+        check_property(default_iterations(), &generator, property);
+    }
+
+    /// Same as [TestContext::forall], but with specified number of `iterations`.
+    pub fn forall_with<G: Generator>(
+        &self,
+        iterations: u32,
+        generator: G,
+        property: impl Fn(G::Value),
+    ) {
+        check_property(iterations, &generator, property);
+    }
+
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn record_check_failure(&self, message: String) {
+        self.check_failures.borrow_mut().push(CheckFailure {
+            location: Location::caller().to_string(),
+            message,
+        });
+    }
+
+    fn take_check_failures(&self) -> Vec<CheckFailure> {
+        std::mem::take(&mut *self.check_failures.borrow_mut())
+    }
+
+    pub(crate) fn take_pending_ticks(&self) -> Option<PendingTicks> {
+        self.pending_ticks.borrow_mut().take()
+    }
+
+    fn restore_engine(&self) {
+        let mut engine = Engine::singleton();
+        if let Some(ticks_per_second) = self.original_physics_ticks.take() {
+            engine.set_physics_ticks_per_second(ticks_per_second);
+        }
+        if let Some(time_scale) = self.original_time_scale.take() {
+            engine.set_time_scale(time_scale);
+        }
+    }
+
+    /// Starts recording emissions of `signal` of the `object`.
+    ///
+    /// ## Panics
+    ///
+    /// If the `object` has no such signal.
+    pub fn watch_signal<T: Inherits<Object>>(
+        &self,
+        object: &Gd<T>,
+        signal: impl Into<StringName>,
+    ) -> SignalSpy {
+        SignalSpy::new(object, signal)
+    }
+
+    pub(crate) fn instantiate_case_scene(&mut self, path: &str) {
+        self.case_scene = Some(self.instantiate_scene::<Node>(path));
+    }
+
+    /// Frees all scenes instantiated with [TestContext::instantiate_scene] and `scene` attribute.
+    pub(crate) fn free_instantiated_scenes(&mut self) {
+        self.case_scene = None;
+        for mut instance in self.instantiated_scenes.get_mut().drain(..) {
+            if instance.is_instance_valid() {
+                // Detach right away, so the instance won't collide with the next case nodes before being freed.
+                if let Some(mut parent) = instance.get_parent() {
+                    parent.remove_child(&instance);
+                }
+                instance.queue_free();
+            }
+        }
+    }
+
+    pub(crate) fn has_added_nodes(&self) -> bool {
+        !self.added_nodes.is_empty()
+    }
+
+    /// Removes all nodes added during the setup procedure.
+    ///
+    /// This method is called after the test if no teardown function is provided, and needs to be called also when implementing custom
+    /// teardown.
+    pub fn remove_all_added_nodes(&mut self) {
+        for node_path in self.added_nodes.drain() {
+            if let Some(node) = self.scene_tree.get_node_or_null(node_path.arg()) {
+                Self::detach_and_free(&mut self.scene_tree, node);
+            }
+        }
+    }
+
+    /// Removes single node added during the setup procedure.
+    ///
+    /// For usage in custom teardown procedure, if you need to remove the nodes in specific order. All nodes need to be removed during
+    /// teardown.
+    pub fn remove_added_node(&mut self, name: impl Into<GString>) {
+        let name: GString = name.into();
+        if let Some(node) = self.scene_tree.get_node_or_null(name.arg()) {
+            Self::detach_and_free(&mut self.scene_tree, node);
+            self.added_nodes.remove(&name);
+        }
+    }
+
+    // Detach right away, so the node won't collide with the nodes set up by the next case before being freed.
+    fn detach_and_free(scene_tree: &mut Gd<Node>, mut node: Gd<Node>) {
+        scene_tree.remove_child(&node);
+        node.queue_free();
+    }
+
+    /// Add node to scene in which the test will be processed.
+    ///
+    /// This method should be called only during setup procedure for a test.
+    ///
+    /// ## Panics
+    ///
+    /// If the scene already contains a node with `name`, as Godot would rename the added node.
+    pub fn setup_add_node(&mut self, node: Gd<Node>, name: impl Into<GString>) {
+        let name = name.into();
+        let mut node = node.clone();
+        node.set_name(&name);
+        self.scene_tree.add_child(&node);
+        let added_name = node.get_name();
+        assert!(
+            added_name == StringName::from(&name),
+            "setup node was added as `{added_name}` instead of `{name}`, as a node with this name is already in the scene"
+        );
+        self.added_nodes.insert(name);
+    }
+
+    /// Gets node from current test context that was set up.
+    ///
+    /// ## Panics
+    ///
+    /// If no node with `name` was set up during setup function.
+    pub fn get_setup_node(&self, name: impl Into<GString>) -> Gd<Node> {
+        let gstring: GString = name.into();
+        if !self.added_nodes.contains(&gstring) {
+            panic!("no node with name: `{gstring}` were set up");
+        }
+        self.scene_tree
+            .get_node_or_null(gstring.arg())
+            .expect("cannot get setup node")
+    }
+
+    /// Gets node from current test context that was set up, upcasted to `T`.
+    ///
+    /// ## Panics
+    ///
+    /// If no node with `name` was set up during setup function, or cannot be upcasted to `T`.
+    pub fn get_setup_node_as<T: Inherits<Node>>(&self, name: impl Into<GString>) -> Gd<T> {
+        let gstring: GString = name.into();
+        if !self.added_nodes.contains(&gstring) {
+            panic!("no node with name: `{gstring}` were set up");
+        }
+        self.scene_tree
+            .try_get_node_as(gstring.arg())
+            .expect("cannot get setup node as")
+    }
+
+    /// Sets the node to which `before_all` function of the suite added its nodes, when it differs from the scene tree of the case.
+    pub(crate) fn set_suite_tree(&mut self, suite_tree: Gd<Node>) {
+        self.suite_tree = suite_tree;
+    }
+
+    /// Gets node located at `path`, relatively to the node to which `before_all` function of `gdsuite!` added its
+    /// nodes.
+    ///
+    /// Same as [CaseContext::get_node], unless the cases are isolated - then the scene tree of the case is its own temporary root,
+    /// while the suite nodes are shared by the cases and stay under [GdTestRunner](crate::runner::GdTestRunner).
+    ///
+    /// ## Panics
+    ///
+    /// If no node is present at the `path`.
+    pub fn get_suite_node(&self, path: impl AsArg<NodePath>) -> Gd<Node> {
+        self.suite_tree
+            .get_node_or_null(path)
+            .expect("couldn't get suite node")
+    }
+
+    /// Gets node located at `path`, relatively to the node to which `before_all` function of `gdsuite!` added its
+    /// nodes, casted to `T`.
+    ///
+    /// ## Panics
+    ///
+    /// If no node is present at the `path`, or cannot be casted to `T`.
+    pub fn get_suite_node_as<T: Inherits<Node>>(&self, path: impl AsArg<NodePath>) -> Gd<T> {
+        self.suite_tree
+            .try_get_node_as(path)
+            .expect("couldn't get suite node as")
     }
 }
 
@@ -69,3 +619,66 @@ impl CaseContext for TestContext {
         &self.scene_tree
     }
 }
+
+#[derive(Debug)]
+pub(crate) enum TestError {
+    Setup(UnwindError),
+    Execution(UnwindError),
+    Teardown(UnwindError),
+    BeforeAll(UnwindError),
+    AfterAll(UnwindError),
+    Isolation(StrayNodesError),
+    NotCleanedUp,
+    Checks {
+        failures: Vec<CheckFailure>,
+        error: Option<Box<TestError>>,
+    },
+    Multiple(Vec<TestError>),
+}
+
+impl Display for TestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TestError::Setup(err) => write!(f, "[setup] {err}"),
+            TestError::Execution(err) => write!(f, "{err}"),
+            TestError::Teardown(err) => write!(f, "[teardown] {err}"),
+            TestError::BeforeAll(err) => write!(f, "[before_all] {err}"),
+            TestError::AfterAll(err) => write!(f, "[after_all] {err}"),
+            TestError::Isolation(err) => write!(f, "[isolation] {err}"),
+            TestError::NotCleanedUp => write!(f, "[teardown] some setup nodes are still present. Call `TestContext::remove_all_added_nodes()` in your teardown or after_all function"),
+            TestError::Checks { failures, error } => {
+                write!(f, "{} check(s) failed:", failures.len())?;
+                for failure in failures {
+                    write!(f, "\n  at {}: {}", failure.location, failure.message)?;
+                }
+                if let Some(err) = error {
+                    write!(f, "\n{err}")?;
+                }
+                Ok(())
+            }
+            TestError::Multiple(errors) => write_multiple(f, errors),
+        }
+    }
+}
+
+impl Error for TestError {}
+
+pub(crate) fn write_multiple<E: Display>(
+    f: &mut std::fmt::Formatter<'_>,
+    errors: &[E],
+) -> std::fmt::Result {
+    for (i, err) in errors.iter().enumerate() {
+        if i > 0 {
+            writeln!(f)?;
+        }
+        write!(f, "{err}")?;
+    }
+    Ok(())
+}
+
+/// Failure recorded by soft assertion of [TestContext].
+#[derive(Debug)]
+pub(crate) struct CheckFailure {
+    location: String,
+    message: String,
+}
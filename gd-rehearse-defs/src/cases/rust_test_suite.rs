@@ -0,0 +1,92 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+use std::panic::AssertUnwindSafe;
+
+use godot::classes::Node;
+use godot::obj::Gd;
+
+use crate::runner::panic::{handle_panic, UnwindError};
+
+use super::rust_test_case::{TestContext, TestError};
+
+/// Rust test suite fixtures, shared by all `#[gditest]` cases declared in the same file.
+///
+/// Registered with `gdsuite!` macro.
+#[derive(Debug, Clone, Copy)]
+pub struct RustTestSuite {
+    pub file: &'static str,
+    pub line: u32,
+    pub before_all: Option<fn(&mut TestContext)>,
+    pub after_all: Option<fn(&mut TestContext)>,
+}
+
+/// State of the suite during the run.
+pub(crate) struct SuiteRun {
+    suite: RustTestSuite,
+    ctx: Option<TestContext>,
+    before_all_error: Option<UnwindError>,
+    remaining_cases: usize,
+}
+
+impl SuiteRun {
+    pub(crate) fn new(suite: RustTestSuite, cases_count: usize) -> Self {
+        Self {
+            suite,
+            ctx: None,
+            before_all_error: None,
+            remaining_cases: cases_count,
+        }
+    }
+
+    /// Executes `before_all` function if the suite wasn't started yet. Returns the `before_all` error for every case of the suite.
+    pub(crate) fn enter(&mut self, scene_tree: &Gd<Node>) -> Result<(), TestError> {
+        if self.ctx.is_none() {
+            let mut ctx = TestContext::new(scene_tree.clone());
+            if let Some(before_all) = self.suite.before_all {
+                if let Err(err) = handle_panic(AssertUnwindSafe(|| (before_all)(&mut ctx))) {
+                    // Nodes added before the panic won't be used by any case.
+                    ctx.remove_all_added_nodes();
+                    ctx.free_instantiated_scenes();
+                    self.before_all_error = Some(err);
+                }
+            }
+            self.ctx = Some(ctx);
+        }
+
+        match &self.before_all_error {
+            Some(err) => Err(TestError::BeforeAll(err.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Marks one case of the suite as finished. After the last one, executes `after_all` function if the suite was started.
+    pub(crate) fn leave(&mut self) -> Result<(), TestError> {
+        self.remaining_cases = self.remaining_cases.saturating_sub(1);
+        if self.remaining_cases > 0 || self.before_all_error.is_some() {
+            return Ok(());
+        }
+        let Some(mut ctx) = self.ctx.take() else {
+            return Ok(());
+        };
+
+        let Some(after_all) = self.suite.after_all else {
+            ctx.remove_all_added_nodes();
+            ctx.free_instantiated_scenes();
+            return Ok(());
+        };
+
+        let result = handle_panic(AssertUnwindSafe(|| (after_all)(&mut ctx)));
+        ctx.free_instantiated_scenes();
+        if ctx.has_added_nodes() {
+            ctx.remove_all_added_nodes();
+            if result.is_ok() {
+                return Err(TestError::NotCleanedUp);
+            }
+        }
+        result.map_err(TestError::AfterAll)
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use godot::builtin::{Callable, StringName, Variant, VariantArray};
+use godot::classes::Object;
+use godot::obj::{Gd, Inherits, InstanceId};
+
+/// Records emissions of a Godot signal.
+///
+/// Created with [TestContext::watch_signal](crate::cases::rust_test_case::TestContext::watch_signal). The signal is disconnected when
+/// the spy is dropped.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_rehearse::itest::*;
+///
+/// #[gditest]
+/// fn emits_child_entered(ctx: &TestContext) {
+///    let spy = ctx.watch_signal(ctx.scene_tree(), "child_entered_tree");
+///
+///    let mut node = Node::new_alloc();
+///    ctx.scene_tree().clone().add_child(&node);
+///    spy.assert_emitted_times(1);
+///    spy.assert_emitted_with(&[node.to_variant()]);
+///    node.queue_free();
+/// }
+/// ```
+pub struct SignalSpy {
+    object_id: InstanceId,
+    object_display: String,
+    signal: StringName,
+    callable: Callable,
+    emissions: Rc<RefCell<Vec<VariantArray>>>,
+}
+
+impl SignalSpy {
+    pub(crate) fn new<T: Inherits<Object>>(object: &Gd<T>, signal: impl Into<StringName>) -> Self {
+        let mut object = object.clone().upcast::<Object>();
+        let signal: StringName = signal.into();
+        let object_display = format!("{}::{}", object.get_class(), object.instance_id());
+
+        if !object.has_signal(&signal) {
+            panic!("`{object_display}` has no signal `{signal}`");
+        }
+
+        let emissions = Rc::new(RefCell::new(Vec::new()));
+        let recorder = emissions.clone();
+        let callable = Callable::from_local_fn(
+            &format!("SignalSpy::{signal}"),
+            move |args: &[&Variant]| {
+                recorder
+                    .borrow_mut()
+                    .push(args.iter().map(|arg| (*arg).clone()).collect());
+                Ok(Variant::nil())
+            },
+        );
+        object.connect(&signal, &callable);
+
+        Self {
+            object_id: object.instance_id(),
+            object_display,
+            signal,
+            callable,
+            emissions,
+        }
+    }
+
+    /// Number of times the signal was emitted since the spy was created.
+    pub fn emitted_count(&self) -> usize {
+        self.emissions.borrow().len()
+    }
+
+    /// Arguments of every recorded emission, in order of emitting.
+    pub fn emissions(&self) -> Vec<VariantArray> {
+        self.emissions.borrow().clone()
+    }
+
+    /// Arguments of the last recorded emission, if the signal was emitted.
+    pub fn last_emission(&self) -> Option<VariantArray> {
+        self.emissions.borrow().last().cloned()
+    }
+
+    /// Forgets all recorded emissions.
+    pub fn clear(&self) {
+        self.emissions.borrow_mut().clear();
+    }
+
+    /// ## Panics
+    ///
+    /// If the signal wasn't emitted.
+    pub fn assert_emitted(&self) {
+        if self.emitted_count() == 0 {
+            panic!("{} was never emitted", self.describe());
+        }
+    }
+
+    /// ## Panics
+    ///
+    /// If the signal was emitted.
+    pub fn assert_not_emitted(&self) {
+        if self.emitted_count() > 0 {
+            panic!(
+                "{} was emitted, but shouldn't be\n{}",
+                self.describe(),
+                self.format_emissions()
+            );
+        }
+    }
+
+    /// ## Panics
+    ///
+    /// If the signal was emitted different number of times than `times`.
+    pub fn assert_emitted_times(&self, times: usize) {
+        let count = self.emitted_count();
+        if count != times {
+            panic!(
+                "{} was emitted {count} times, expected {times}\n{}",
+                self.describe(),
+                self.format_emissions()
+            );
+        }
+    }
+
+    /// ## Panics
+    ///
+    /// If the signal wasn't emitted with exactly `args` at least once.
+    pub fn assert_emitted_with(&self, args: &[Variant]) {
+        let expected = args.iter().cloned().collect::<VariantArray>();
+        if !self.emissions.borrow().contains(&expected) {
+            panic!(
+                "{} wasn't emitted with expected arguments\n  expected: {}\n{}",
+                self.describe(),
+                Self::format_args(&expected),
+                self.format_emissions()
+            );
+        }
+    }
+
+    /// ## Panics
+    ///
+    /// If the signal wasn't emitted, or was last emitted with other arguments than `args`.
+    pub fn assert_last_emitted_with(&self, args: &[Variant]) {
+        let expected = args.iter().cloned().collect::<VariantArray>();
+        match self.last_emission() {
+            Some(last) if last == expected => {}
+            Some(last) => panic!(
+                "{} was last emitted with other arguments\n  expected: {}\n    actual: {}",
+                self.describe(),
+                Self::format_args(&expected),
+                Self::format_args(&last)
+            ),
+            None => panic!(
+                "{} was never emitted\n  expected: {}",
+                self.describe(),
+                Self::format_args(&expected)
+            ),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("signal `{}` of `{}`", self.signal, self.object_display)
+    }
+
+    fn format_args(args: &VariantArray) -> String {
+        let args = args
+            .iter_shared()
+            .map(|arg| format!("{arg:?}"))
+            .collect::<Vec<_>>();
+        format!("({})", args.join(", "))
+    }
+
+    fn format_emissions(&self) -> String {
+        let emissions = self.emissions.borrow();
+        if emissions.is_empty() {
+            return "  recorded: none".to_owned();
+        }
+        let mut out = String::from("  recorded:");
+        for (i, emission) in emissions.iter().enumerate() {
+            out.push_str(&format!(
+                "\n    #{}: {}",
+                i + 1,
+                Self::format_args(emission)
+            ));
+        }
+        out
+    }
+}
+
+impl Drop for SignalSpy {
+    fn drop(&mut self) {
+        if let Ok(mut object) = Gd::<Object>::try_from_instance_id(self.object_id) {
+            if object.is_connected(&self.signal, &self.callable) {
+                object.disconnect(&self.signal, &self.callable);
+            }
+        }
+    }
+}
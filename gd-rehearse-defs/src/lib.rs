@@ -4,9 +4,12 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+pub mod assertions;
 pub mod cases;
+pub mod property;
 pub mod registry;
 pub mod runner;
+pub mod snapshot;
 
 pub mod paste {
     pub use paste::paste;
@@ -0,0 +1,687 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Property-based testing with generators of Godot values.
+//!
+//! Property is checked for many generated inputs with [`TestContext::forall`](crate::cases::rust_test_case::TestContext::forall). Failing
+//! input is shrunk to a minimal one, which is reported together with the seed. Seed can be provided with `--prop-seed=N` argument to
+//! reproduce the failure, and the default number of iterations can be changed with `--prop-iterations=N`.
+
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::Mutex;
+
+use godot::builtin::{
+    real, Color, Dictionary, GString, PackedByteArray, StringName, Variant, VariantType, Vector2,
+    Vector3,
+};
+use godot::meta::ToGodot;
+
+use crate::registry::SplitMix64;
+use crate::runner::panic::{handle_panic, UnwindError};
+
+/// Number of iterations used if not set with `--prop-iterations` argument.
+pub const DEFAULT_ITERATIONS: u32 = 100;
+
+const MAX_SHRINK_STEPS: u32 = 1000;
+
+struct PropertySettings {
+    seed: Option<u64>,
+    iterations: u32,
+}
+
+static SETTINGS: Mutex<PropertySettings> = Mutex::new(PropertySettings {
+    seed: None,
+    iterations: DEFAULT_ITERATIONS,
+});
+
+pub(crate) fn set_property_settings(seed: Option<u64>, iterations: Option<u32>) {
+    let mut settings = SETTINGS.lock().unwrap_or_else(|err| err.into_inner());
+    settings.seed = seed;
+    settings.iterations = iterations.unwrap_or(DEFAULT_ITERATIONS);
+}
+
+pub(crate) fn default_iterations() -> u32 {
+    SETTINGS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .iterations
+}
+
+fn resolve_seed() -> u64 {
+    if let Some(seed) = SETTINGS.lock().unwrap_or_else(|err| err.into_inner()).seed {
+        return seed;
+    }
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+/// Source of randomness for [Generator]s. The same seed always produces the same values.
+pub struct Rng {
+    inner: SplitMix64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: SplitMix64::new(seed),
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    /// Random index lower than `len`, which needs to be greater than 0.
+    pub fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn i64_in(&mut self, range: Range<i64>) -> i64 {
+        let span = range.end.wrapping_sub(range.start) as u64;
+        if span == 0 {
+            return range.start;
+        }
+        range.start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    pub fn f64_in(&mut self, range: Range<f64>) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64;
+        range.start + (range.end - range.start) * unit
+    }
+}
+
+/// Generates random values of some type, and proposes simpler values when shrinking the failing input.
+pub trait Generator {
+    type Value: Clone + Debug;
+
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    /// Candidates simpler than `value`, from the simplest. Every candidate needs to be strictly simpler, so the shrinking ends.
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        Vec::new()
+    }
+}
+
+macro_rules! impl_tuple_generator {
+    ($($gen:ident $idx:tt),+) => {
+        impl<$($gen: Generator),+> Generator for ($($gen,)+) {
+            type Value = ($($gen::Value,)+);
+
+            fn generate(&self, rng: &mut Rng) -> Self::Value {
+                ($(self.$idx.generate(rng),)+)
+            }
+
+            // Shrinks one element at a time, leaving the rest unchanged.
+            fn shrink(&self, value: &Self::Value) -> Vec<Self::Value> {
+                let mut candidates = Vec::new();
+                $(
+                    for shrunk in self.$idx.shrink(&value.$idx) {
+                        let mut candidate = value.clone();
+                        candidate.$idx = shrunk;
+                        candidates.push(candidate);
+                    }
+                )+
+                candidates
+            }
+        }
+    };
+}
+
+impl_tuple_generator!(A 0, B 1);
+impl_tuple_generator!(A 0, B 1, C 2);
+impl_tuple_generator!(A 0, B 1, C 2, D 3);
+
+/// Checks `property` for `iterations` inputs generated by `generator`, panicking with the shrunk failing input and the seed.
+pub(crate) fn check_property<G, F>(iterations: u32, generator: &G, property: F)
+where
+    G: Generator,
+    F: Fn(G::Value),
+{
+    let seed = resolve_seed();
+    let mut rng = Rng::new(seed);
+    let run = |value: G::Value| handle_panic(std::panic::AssertUnwindSafe(|| property(value)));
+
+    // Panics of the checked inputs are expected, so they shouldn't be printed.
+    let silenced = SilencedPanics::new();
+
+    let mut failure = None;
+    for iteration in 1..=iterations {
+        let value = generator.generate(&mut rng);
+        if let Err(err) = run(value.clone()) {
+            failure = Some((iteration, value, err));
+            break;
+        }
+    }
+    let failure = failure.map(|(iteration, original, err)| {
+        let (shrunk, steps, err) = shrink_failure(generator, original.clone(), err, run);
+        (iteration, original, shrunk, steps, err)
+    });
+
+    drop(silenced);
+
+    if let Some((iteration, original, shrunk, steps, err)) = failure {
+        panic!(
+            "property failed after {iteration} iteration(s) with seed {seed}. Rerun with `--prop-seed={seed}` to reproduce\n  \
+            minimal input ({steps} shrink step(s)): {shrunk:?}\n  original input: {original:?}\n{err}"
+        );
+    }
+}
+
+type PanicHook = Box<dyn Fn(&std::panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// Silences the panic messages while alive. Previous hook is restored on drop, so also when generator panics.
+struct SilencedPanics {
+    hook: Option<PanicHook>,
+}
+
+impl SilencedPanics {
+    fn new() -> Self {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        Self { hook: Some(hook) }
+    }
+}
+
+impl Drop for SilencedPanics {
+    fn drop(&mut self) {
+        if let Some(hook) = self.hook.take() {
+            std::panic::set_hook(hook);
+        }
+    }
+}
+
+fn shrink_failure<G: Generator>(
+    generator: &G,
+    mut value: G::Value,
+    mut err: UnwindError,
+    run: impl Fn(G::Value) -> Result<(), UnwindError>,
+) -> (G::Value, u32, UnwindError) {
+    let mut steps = 0;
+    'shrinking: while steps < MAX_SHRINK_STEPS {
+        for candidate in generator.shrink(&value) {
+            if let Err(candidate_err) = run(candidate.clone()) {
+                value = candidate;
+                err = candidate_err;
+                steps += 1;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    (value, steps, err)
+}
+
+/// Generators of Rust primitives and Godot builtins.
+pub mod generators {
+    use super::*;
+
+    /// Generator created by [any_bool].
+    pub struct BoolGenerator;
+
+    impl Generator for BoolGenerator {
+        type Value = bool;
+
+        fn generate(&self, rng: &mut Rng) -> bool {
+            rng.bool()
+        }
+
+        fn shrink(&self, value: &bool) -> Vec<bool> {
+            if *value {
+                vec![false]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn any_bool() -> BoolGenerator {
+        BoolGenerator
+    }
+
+    /// Generator created by [any_i64].
+    pub struct IntGenerator {
+        range: Range<i64>,
+    }
+
+    impl Generator for IntGenerator {
+        type Value = i64;
+
+        fn generate(&self, rng: &mut Rng) -> i64 {
+            rng.i64_in(self.range.clone())
+        }
+
+        fn shrink(&self, value: &i64) -> Vec<i64> {
+            shrink_i64(*value, shrink_target_i64(&self.range))
+        }
+    }
+
+    /// Integers from `range`, shrunk towards 0, or the start of the range if it doesn't contain 0.
+    pub fn any_i64(range: Range<i64>) -> IntGenerator {
+        IntGenerator { range }
+    }
+
+    /// Generator created by [any_f64].
+    pub struct FloatGenerator {
+        range: Range<f64>,
+    }
+
+    impl Generator for FloatGenerator {
+        type Value = f64;
+
+        fn generate(&self, rng: &mut Rng) -> f64 {
+            rng.f64_in(self.range.clone())
+        }
+
+        fn shrink(&self, value: &f64) -> Vec<f64> {
+            shrink_f64(*value, shrink_target_f64(&self.range))
+        }
+    }
+
+    /// Floats from `range`, shrunk towards 0, or the start of the range if it doesn't contain 0.
+    pub fn any_f64(range: Range<f64>) -> FloatGenerator {
+        FloatGenerator { range }
+    }
+
+    /// Generator created by [vector2].
+    pub struct Vector2Generator {
+        range: Range<f64>,
+    }
+
+    impl Generator for Vector2Generator {
+        type Value = Vector2;
+
+        fn generate(&self, rng: &mut Rng) -> Vector2 {
+            Vector2::new(
+                rng.f64_in(self.range.clone()) as real,
+                rng.f64_in(self.range.clone()) as real,
+            )
+        }
+
+        fn shrink(&self, value: &Vector2) -> Vec<Vector2> {
+            let target = shrink_target_f64(&self.range);
+            let mut candidates = Vec::new();
+            for x in shrink_f64(value.x as f64, target) {
+                candidates.push(Vector2::new(x as real, value.y));
+            }
+            for y in shrink_f64(value.y as f64, target) {
+                candidates.push(Vector2::new(value.x, y as real));
+            }
+            candidates
+        }
+    }
+
+    /// `Vector2` with components from `range`.
+    pub fn vector2(range: Range<f64>) -> Vector2Generator {
+        Vector2Generator { range }
+    }
+
+    /// Generator created by [vector3].
+    pub struct Vector3Generator {
+        range: Range<f64>,
+    }
+
+    impl Generator for Vector3Generator {
+        type Value = Vector3;
+
+        fn generate(&self, rng: &mut Rng) -> Vector3 {
+            Vector3::new(
+                rng.f64_in(self.range.clone()) as real,
+                rng.f64_in(self.range.clone()) as real,
+                rng.f64_in(self.range.clone()) as real,
+            )
+        }
+
+        fn shrink(&self, value: &Vector3) -> Vec<Vector3> {
+            let target = shrink_target_f64(&self.range);
+            let mut candidates = Vec::new();
+            for x in shrink_f64(value.x as f64, target) {
+                candidates.push(Vector3::new(x as real, value.y, value.z));
+            }
+            for y in shrink_f64(value.y as f64, target) {
+                candidates.push(Vector3::new(value.x, y as real, value.z));
+            }
+            for z in shrink_f64(value.z as f64, target) {
+                candidates.push(Vector3::new(value.x, value.y, z as real));
+            }
+            candidates
+        }
+    }
+
+    /// `Vector3` with components from `range`.
+    pub fn vector3(range: Range<f64>) -> Vector3Generator {
+        Vector3Generator { range }
+    }
+
+    /// Generator created by [color].
+    pub struct ColorGenerator;
+
+    impl Generator for ColorGenerator {
+        type Value = Color;
+
+        fn generate(&self, rng: &mut Rng) -> Color {
+            Color::from_rgba(
+                rng.f64_in(0.0..1.0) as f32,
+                rng.f64_in(0.0..1.0) as f32,
+                rng.f64_in(0.0..1.0) as f32,
+                rng.f64_in(0.0..1.0) as f32,
+            )
+        }
+
+        fn shrink(&self, value: &Color) -> Vec<Color> {
+            let mut candidates = Vec::new();
+            let components = [value.r, value.g, value.b, value.a];
+            for (i, component) in components.iter().enumerate() {
+                for shrunk in shrink_f64(*component as f64, 0.0) {
+                    let mut shrunk_components = components;
+                    shrunk_components[i] = shrunk as f32;
+                    let [r, g, b, a] = shrunk_components;
+                    candidates.push(Color::from_rgba(r, g, b, a));
+                }
+            }
+            candidates
+        }
+    }
+
+    /// `Color` with all components, including alpha, from `0.0..1.0`.
+    pub fn color() -> ColorGenerator {
+        ColorGenerator
+    }
+
+    /// Generator created by [gstring].
+    pub struct GStringGenerator {
+        max_len: usize,
+    }
+
+    impl Generator for GStringGenerator {
+        type Value = GString;
+
+        fn generate(&self, rng: &mut Rng) -> GString {
+            GString::from(generate_string(rng, self.max_len).as_str())
+        }
+
+        fn shrink(&self, value: &GString) -> Vec<GString> {
+            shrink_string(&value.to_string())
+                .iter()
+                .map(|string| GString::from(string.as_str()))
+                .collect()
+        }
+    }
+
+    /// `GString` of up to `max_len` characters, including non-ASCII ones.
+    pub fn gstring(max_len: usize) -> GStringGenerator {
+        GStringGenerator { max_len }
+    }
+
+    /// Generator created by [string_name].
+    pub struct StringNameGenerator {
+        max_len: usize,
+    }
+
+    impl Generator for StringNameGenerator {
+        type Value = StringName;
+
+        fn generate(&self, rng: &mut Rng) -> StringName {
+            StringName::from(generate_string(rng, self.max_len).as_str())
+        }
+
+        fn shrink(&self, value: &StringName) -> Vec<StringName> {
+            shrink_string(&value.to_string())
+                .iter()
+                .map(|string| StringName::from(string.as_str()))
+                .collect()
+        }
+    }
+
+    /// `StringName` of up to `max_len` characters, including non-ASCII ones.
+    pub fn string_name(max_len: usize) -> StringNameGenerator {
+        StringNameGenerator { max_len }
+    }
+
+    /// Generator created by [packed_byte_array].
+    pub struct PackedByteArrayGenerator {
+        max_len: usize,
+    }
+
+    impl Generator for PackedByteArrayGenerator {
+        type Value = PackedByteArray;
+
+        fn generate(&self, rng: &mut Rng) -> PackedByteArray {
+            let len = rng.index(self.max_len + 1);
+            let bytes = (0..len).map(|_| rng.next_u64() as u8).collect::<Vec<_>>();
+            PackedByteArray::from(bytes.as_slice())
+        }
+
+        fn shrink(&self, value: &PackedByteArray) -> Vec<PackedByteArray> {
+            shrink_vec(value.as_slice(), |byte| {
+                shrink_i64(*byte as i64, 0)
+                    .into_iter()
+                    .map(|byte| byte as u8)
+                    .collect()
+            })
+            .iter()
+            .map(|bytes| PackedByteArray::from(bytes.as_slice()))
+            .collect()
+        }
+    }
+
+    /// `PackedByteArray` of up to `max_len` bytes.
+    pub fn packed_byte_array(max_len: usize) -> PackedByteArrayGenerator {
+        PackedByteArrayGenerator { max_len }
+    }
+
+    /// Generator created by [variant].
+    pub struct VariantGenerator;
+
+    impl Generator for VariantGenerator {
+        type Value = Variant;
+
+        fn generate(&self, rng: &mut Rng) -> Variant {
+            match rng.index(10) {
+                0 => Variant::nil(),
+                1 => rng.bool().to_variant(),
+                2 => rng.i64_in(-1000..1000).to_variant(),
+                3 => rng.f64_in(-1000.0..1000.0).to_variant(),
+                4 => gstring(16).generate(rng).to_variant(),
+                5 => string_name(16).generate(rng).to_variant(),
+                6 => vector2(-1000.0..1000.0).generate(rng).to_variant(),
+                7 => vector3(-1000.0..1000.0).generate(rng).to_variant(),
+                8 => color().generate(rng).to_variant(),
+                _ => packed_byte_array(16).generate(rng).to_variant(),
+            }
+        }
+
+        // Every value shrinks to `nil` first, then to simpler values of the same type.
+        fn shrink(&self, value: &Variant) -> Vec<Variant> {
+            fn shrunk<G: Generator>(generator: G, value: &G::Value) -> Vec<Variant>
+            where
+                G::Value: ToGodot,
+            {
+                generator
+                    .shrink(value)
+                    .iter()
+                    .map(|value| value.to_variant())
+                    .collect()
+            }
+
+            let mut candidates = match value.get_type() {
+                VariantType::NIL => return Vec::new(),
+                VariantType::BOOL => shrunk(any_bool(), &value.to::<bool>()),
+                VariantType::INT => shrunk(any_i64(i64::MIN..i64::MAX), &value.to::<i64>()),
+                VariantType::FLOAT => shrunk(any_f64(f64::MIN..f64::MAX), &value.to::<f64>()),
+                VariantType::STRING => shrunk(gstring(0), &value.to::<GString>()),
+                VariantType::STRING_NAME => shrunk(string_name(0), &value.to::<StringName>()),
+                VariantType::VECTOR2 => shrunk(vector2(-1.0..1.0), &value.to::<Vector2>()),
+                VariantType::VECTOR3 => shrunk(vector3(-1.0..1.0), &value.to::<Vector3>()),
+                VariantType::COLOR => shrunk(color(), &value.to::<Color>()),
+                VariantType::PACKED_BYTE_ARRAY => {
+                    shrunk(packed_byte_array(0), &value.to::<PackedByteArray>())
+                }
+                _ => Vec::new(),
+            };
+            candidates.insert(0, Variant::nil());
+            candidates
+        }
+    }
+
+    /// Arbitrary `Variant`: `nil`, `bool`, `int`, `float`, `String`, `StringName`, `Vector2`, `Vector3`, `Color` or
+    /// `PackedByteArray`.
+    pub fn variant() -> VariantGenerator {
+        VariantGenerator
+    }
+
+    /// Generator created by [dictionary].
+    pub struct DictionaryGenerator {
+        max_len: usize,
+    }
+
+    impl Generator for DictionaryGenerator {
+        type Value = Dictionary;
+
+        fn generate(&self, rng: &mut Rng) -> Dictionary {
+            let len = rng.index(self.max_len + 1);
+            let mut dict = Dictionary::new();
+            for _ in 0..len {
+                dict.set(gstring(8).generate(rng), variant().generate(rng));
+            }
+            dict
+        }
+
+        fn shrink(&self, value: &Dictionary) -> Vec<Dictionary> {
+            let entries = value.iter_shared().collect::<Vec<_>>();
+            shrink_vec(&entries, |(key, value)| {
+                variant()
+                    .shrink(value)
+                    .into_iter()
+                    .map(|value| (key.clone(), value))
+                    .collect()
+            })
+            .into_iter()
+            .map(|entries| {
+                let mut dict = Dictionary::new();
+                for (key, value) in entries {
+                    dict.set(key, value);
+                }
+                dict
+            })
+            .collect()
+        }
+    }
+
+    /// `Dictionary` of up to `max_len` entries, with `String` keys and [variant] values.
+    pub fn dictionary(max_len: usize) -> DictionaryGenerator {
+        DictionaryGenerator { max_len }
+    }
+
+    const CHARS: &[char] = &[
+        'a', 'b', 'z', 'A', 'Z', '0', '9', ' ', '_', '-', '/', '.', '"', '\n', 'é', 'ß', 'ж', '日',
+        '😀',
+    ];
+
+    fn generate_string(rng: &mut Rng, max_len: usize) -> String {
+        let len = rng.index(max_len + 1);
+        (0..len).map(|_| CHARS[rng.index(CHARS.len())]).collect()
+    }
+
+    fn shrink_string(value: &str) -> Vec<String> {
+        let chars = value.chars().collect::<Vec<_>>();
+        shrink_vec(
+            &chars,
+            |char| {
+                if *char == 'a' {
+                    Vec::new()
+                } else {
+                    vec!['a']
+                }
+            },
+        )
+        .into_iter()
+        .map(|chars| chars.into_iter().collect())
+        .collect()
+    }
+
+    /// Removes all elements, halves and single elements first, then simplifies every element.
+    fn shrink_vec<T: Clone>(value: &[T], shrink_element: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+        if value.is_empty() {
+            return Vec::new();
+        }
+        let mut candidates = vec![Vec::new()];
+        if value.len() > 2 {
+            let half = value.len() / 2;
+            candidates.push(value[..half].to_vec());
+            candidates.push(value[half..].to_vec());
+        }
+        if value.len() > 1 {
+            for i in 0..value.len() {
+                let mut candidate = value.to_vec();
+                candidate.remove(i);
+                candidates.push(candidate);
+            }
+        }
+        for (i, element) in value.iter().enumerate() {
+            if let Some(simpler) = shrink_element(element).into_iter().next() {
+                let mut candidate = value.to_vec();
+                candidate[i] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+
+    fn shrink_target_i64(range: &Range<i64>) -> i64 {
+        if range.contains(&0) {
+            0
+        } else {
+            range.start
+        }
+    }
+
+    fn shrink_target_f64(range: &Range<f64>) -> f64 {
+        if range.contains(&0.0) {
+            0.0
+        } else {
+            range.start
+        }
+    }
+
+    fn shrink_i64(value: i64, target: i64) -> Vec<i64> {
+        if value == target {
+            return Vec::new();
+        }
+        let mut candidates = vec![target];
+        let halfway = target + (value - target) / 2;
+        if halfway != target && halfway != value {
+            candidates.push(halfway);
+        }
+        let closer = value - (value - target).signum();
+        if !candidates.contains(&closer) {
+            candidates.push(closer);
+        }
+        candidates
+    }
+
+    fn shrink_f64(value: f64, target: f64) -> Vec<f64> {
+        if value == target || !value.is_finite() {
+            return Vec::new();
+        }
+        let mut candidates = vec![target];
+        let truncated = value.trunc();
+        if truncated != value && (truncated - target).abs() < (value - target).abs() {
+            candidates.push(truncated);
+        }
+        let halfway = target + (value - target) / 2.0;
+        if halfway != target && halfway != value {
+            candidates.push(halfway);
+        }
+        candidates
+    }
+}
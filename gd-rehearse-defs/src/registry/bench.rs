@@ -7,9 +7,10 @@
 use std::time::Duration;
 
 use crate::cases::rust_bench::{BenchError, RustBenchmark};
-use crate::cases::CaseOutcome;
+use crate::cases::{CaseOutcome, CaseType};
+use crate::runner::config::RunnerConfig;
 
-use super::CaseFilterer;
+use super::{CaseFilterer, DuplicateCasesError};
 
 pub(crate) const WARMUP_RUNS: usize = 200;
 pub(crate) const TEST_RUNS: usize = 501; // uneven, so median need not be interpolated.
@@ -33,7 +34,7 @@ impl GdBenchmarks {
         self.files_count
     }
 
-    pub(crate) fn init() -> Self {
+    pub(crate) fn init() -> Result<Self, DuplicateCasesError> {
         let mut instance = Self {
             benches: Vec::new(),
             files_count: 0,
@@ -41,7 +42,9 @@ impl GdBenchmarks {
         };
 
         instance.collect_rust_benchmarks();
-        instance
+        instance.check_duplicates(CaseType::RustBenchmark)?;
+
+        Ok(instance)
     }
 
     pub fn get_post_init_summary(&self) -> String {
@@ -69,8 +72,13 @@ impl GdBenchmarks {
         }
     }
 
-    pub fn finish_setup(&mut self) {
+    pub fn finish_setup(&mut self, config: &RunnerConfig) {
+        self.filter_only_case_file(config);
+        self.filter_shard(config);
         self.sort_cases();
+        if let Some(seed) = config.shuffle_seed() {
+            self.shuffle_cases(seed);
+        }
         self.files_count = self.get_files_count()
     }
 }
@@ -79,14 +87,16 @@ pub(crate) struct BenchResult {
     pub outcome: CaseOutcome,
     pub stats: [Duration; METRIC_COUNT],
     pub error: Option<BenchError>,
+    pub message: Option<String>,
 }
 
 impl BenchResult {
-    pub fn skipped() -> Self {
+    pub fn skipped(skip_message: Option<String>) -> Self {
         Self {
             outcome: CaseOutcome::Skipped,
             stats: [Duration::ZERO, Duration::ZERO],
             error: None,
+            message: skip_message,
         }
     }
 
@@ -95,6 +105,7 @@ impl BenchResult {
             outcome: CaseOutcome::Failed,
             stats: [Duration::ZERO, Duration::ZERO],
             error: Some(err),
+            message: None,
         }
     }
 
@@ -129,6 +140,15 @@ impl BenchResult {
             outcome: CaseOutcome::Passed,
             stats: [min, median],
             error: None,
+            message: None,
+        }
+    }
+
+    /// Fails the result with `err`, keeping the error it already failed with.
+    pub fn fail_also(self, err: BenchError) -> Self {
+        match self.error {
+            Some(previous) => Self::failed(BenchError::Multiple(vec![previous, err])),
+            None => Self::failed(err),
         }
     }
 }
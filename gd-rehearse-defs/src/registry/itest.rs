@@ -4,18 +4,27 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
 */
 
+use std::collections::{BTreeMap, HashMap};
+
 use crate::{
-    cases::{rust_test_case::RustTestCase, CaseOutcome},
-    runner::panic::UnwindError,
+    cases::{
+        rust_test_case::{RustTestCase, TestError},
+        rust_test_suite::{RustTestSuite, SuiteRun},
+        CaseOutcome, CaseType,
+    },
+    runner::config::RunnerConfig,
 };
 
-use super::CaseFilterer;
+use super::{CaseFilterer, DuplicateCasesError};
 
 godot::sys::plugin_registry!(pub GD_REHEARSE_RUST_TEST_CASES: RustTestCase);
+godot::sys::plugin_registry!(pub GD_REHEARSE_RUST_TEST_SUITES: RustTestSuite);
 
 #[doc(hidden)]
 pub(crate) struct GdRustItests {
     tests: Vec<RustTestCase>,
+    suites: HashMap<&'static str, RustTestSuite>,
+    suite_runs: HashMap<&'static str, SuiteRun>,
     files_count: usize,
     is_focus_run: bool,
 }
@@ -33,16 +42,20 @@ impl GdRustItests {
         self.files_count
     }
 
-    pub(crate) fn init() -> Self {
+    pub(crate) fn init() -> Result<Self, DuplicateCasesError> {
         let mut instance = Self {
             tests: Vec::new(),
+            suites: HashMap::new(),
+            suite_runs: HashMap::new(),
             files_count: 0,
             is_focus_run: false,
         };
 
         instance.collect_rust_tests();
+        instance.check_duplicates(CaseType::RustTest)?;
+        instance.collect_rust_suites()?;
 
-        instance
+        Ok(instance)
     }
 
     pub fn get_post_init_summary(&self) -> String {
@@ -66,15 +79,77 @@ impl GdRustItests {
         }
     }
 
-    pub fn finish_setup(&mut self) {
+    fn get_rust_suite() -> Option<RustTestSuite> {
+        GD_REHEARSE_RUST_TEST_SUITES
+            .lock()
+            .expect("can't retrieve RustTestSuite")
+            .pop()
+    }
+
+    // Only one suite can be declared in a file.
+    fn collect_rust_suites(&mut self) -> Result<(), DuplicateCasesError> {
+        let mut locations: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+        while let Some(suite) = Self::get_rust_suite() {
+            locations
+                .entry(suite.file)
+                .or_default()
+                .push(format!("{}:{}", suite.file, suite.line));
+            self.suites.insert(suite.file, suite);
+        }
+
+        let duplicates = locations
+            .into_iter()
+            .filter(|(_, locations)| locations.len() > 1)
+            .map(|(file, locations)| (format!("gdsuite! in {file}"), locations))
+            .collect::<Vec<_>>();
+
+        if duplicates.is_empty() {
+            return Ok(());
+        }
+        Err(DuplicateCasesError {
+            kind: CaseType::RustTest,
+            duplicates,
+        })
+    }
+
+    pub fn finish_setup(&mut self, config: &RunnerConfig) {
+        self.filter_only_case_file(config);
+        self.filter_shard(config);
         self.sort_cases();
-        self.files_count = self.get_files_count()
+        if let Some(seed) = config.shuffle_seed() {
+            self.shuffle_cases(seed);
+        }
+        self.files_count = self.get_files_count();
+        self.prepare_suite_runs();
+    }
+
+    // Suites are run only for files with cases left after filtering.
+    fn prepare_suite_runs(&mut self) {
+        let mut counts: HashMap<&'static str, usize> = HashMap::new();
+        for test in self.tests.iter() {
+            *counts.entry(test.file).or_default() += 1;
+        }
+        self.suite_runs = self
+            .suites
+            .iter()
+            .filter_map(|(file, suite)| {
+                counts
+                    .get(file)
+                    .map(|count| (*file, SuiteRun::new(*suite, *count)))
+            })
+            .collect();
+    }
+
+    /// Gets state of the suite declared in the same file as the test, if there is any.
+    pub(crate) fn get_suite_run(&mut self, test: &RustTestCase) -> Option<&mut SuiteRun> {
+        self.suite_runs.get_mut(test.file)
     }
 }
 
 pub(crate) struct TestResult {
     pub(crate) outcome: CaseOutcome,
-    pub(crate) error: Option<UnwindError>,
+    pub(crate) error: Option<TestError>,
+    pub(crate) message: Option<String>,
 }
 
 impl TestResult {
@@ -82,20 +157,55 @@ impl TestResult {
         Self {
             outcome: CaseOutcome::Passed,
             error: None,
+            message: None,
         }
     }
 
-    pub fn skipped() -> Self {
+    pub fn flaky(attempts: u32) -> Self {
+        Self {
+            outcome: CaseOutcome::Flaky,
+            error: None,
+            message: Some(format!("passed after {attempts} attempts")),
+        }
+    }
+
+    pub fn skipped(skip_message: Option<String>) -> Self {
         Self {
             outcome: CaseOutcome::Skipped,
             error: None,
+            message: skip_message,
         }
     }
 
-    pub fn failed(err: UnwindError) -> Self {
+    pub fn failed(err: TestError) -> Self {
         Self {
             outcome: CaseOutcome::Failed,
             error: Some(err),
+            message: None,
+        }
+    }
+
+    pub fn expected_failure(reason: &str) -> Self {
+        Self {
+            outcome: CaseOutcome::ExpectedFailure,
+            error: None,
+            message: Some(reason.to_owned()),
+        }
+    }
+
+    pub fn unexpected_pass(reason: &str) -> Self {
+        Self {
+            outcome: CaseOutcome::UnexpectedPass,
+            error: None,
+            message: Some(format!("expected to fail: {reason}")),
+        }
+    }
+
+    /// Fails the result with `err`, keeping the error it already failed with.
+    pub fn fail_also(self, err: TestError) -> Self {
+        match self.error {
+            Some(previous) => Self::failed(TestError::Multiple(vec![previous, err])),
+            None => Self::failed(err),
         }
     }
 }
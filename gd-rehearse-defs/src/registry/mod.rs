@@ -4,9 +4,15 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
 */
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt::Display;
+use std::path::Path;
 
-use crate::{cases::Case, runner::config::RunnerConfig};
+use crate::{
+    cases::{Case, CaseType},
+    runner::config::RunnerConfig,
+};
 
 pub mod bench;
 pub mod itest;
@@ -20,12 +26,38 @@ where
     fn get_cases(&self) -> &Vec<T>;
     fn get_cases_mut(&mut self) -> &mut Vec<T>;
 
-    // Filter on path and keyword
-    fn filter_path_keyword(&mut self, config: &RunnerConfig) {
-        // Retain only the ones with the specified path and keyword
+    // Check if every case has unique qualified name
+    fn check_duplicates(&self, kind: CaseType) -> Result<(), DuplicateCasesError> {
+        let mut locations: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for case in self.get_cases().iter() {
+            locations
+                .entry(case.get_case_qualified_name())
+                .or_default()
+                .push(format!("{}:{}", case.get_case_file(), case.get_case_line()));
+        }
+
+        let duplicates = locations
+            .into_iter()
+            .filter(|(_, locations)| locations.len() > 1)
+            .collect::<Vec<_>>();
+
+        if duplicates.is_empty() {
+            return Ok(());
+        }
+        Err(DuplicateCasesError { kind, duplicates })
+    }
+
+    // Filter on path, keyword and tags
+    fn filter_path_keyword_tags(&mut self, config: &RunnerConfig) {
+        // Retain only the ones with the specified path, keyword and tags
         self.get_cases_mut().retain(|t| {
             t.should_run_scene_path(config.scene_path(), config.only_scene_path())
                 && t.should_run_keyword(config.keyword(), config.ignore_keywords())
+                && t.should_run_tags(
+                    config.tags(),
+                    config.exclude_tags(),
+                    config.match_all_tags(),
+                )
         });
     }
 
@@ -48,6 +80,46 @@ where
         self.get_cases_mut().sort_by(|a, b| Case::order(a, b))
     }
 
+    // Retain only the case or cases of the file selected to run in separate process, and the cases from files selected in watch mode.
+    fn filter_only_case_file(&mut self, config: &RunnerConfig) {
+        if let Some(name) = config.only_case() {
+            self.get_cases_mut()
+                .retain(|c| c.get_case_qualified_name() == name);
+        }
+        if let Some(file) = config.only_file() {
+            self.get_cases_mut().retain(|c| c.get_case_file() == file);
+        }
+        if !config.files().is_empty() {
+            self.get_cases_mut().retain(|c| {
+                let case_file = Path::new(c.get_case_file());
+                config
+                    .files()
+                    .iter()
+                    .any(|file| case_file.ends_with(file) || Path::new(file).ends_with(case_file))
+            });
+        }
+    }
+
+    // Retain only cases assigned to the shard. Assignment depends only on the qualified name, so it doesn't change when other cases
+    // are added or removed.
+    fn filter_shard(&mut self, config: &RunnerConfig) {
+        let Some(shard) = config.shard() else {
+            return;
+        };
+        self.get_cases_mut()
+            .retain(|c| stable_hash(&c.get_case_qualified_name()) % shard.count == shard.index - 1);
+    }
+
+    // Shuffle in order reproducible with the same seed
+    fn shuffle_cases(&mut self, seed: u64) {
+        let mut rng = SplitMix64::new(seed);
+        let cases = self.get_cases_mut();
+        for i in (1..cases.len()).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            cases.swap(i, j);
+        }
+    }
+
     // Get files
     fn get_files_count(&self) -> usize {
         let mut set = HashSet::new();
@@ -57,3 +129,126 @@ where
         set.len()
     }
 }
+
+/// FNV-1a hash, which in contrast to `std` hashers is guaranteed to be stable across platforms and versions.
+fn stable_hash(value: &str) -> u64 {
+    value.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+/// Minimal pseudo-random generator, so the shuffled order and generated values are the same for given seed across platforms and
+/// versions.
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Error returned when more than one case is registered under the same qualified name.
+#[derive(Debug)]
+pub(crate) struct DuplicateCasesError {
+    pub(super) kind: CaseType,
+    pub(super) duplicates: Vec<(String, Vec<String>)>,
+}
+
+impl Display for DuplicateCasesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "found Rust {} registered with duplicated names:",
+            self.kind.for_summary().to_lowercase()
+        )?;
+        for (name, locations) in self.duplicates.iter() {
+            write!(f, "\n  * {name}: {}", locations.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for DuplicateCasesError {}
+
+#[cfg(test)]
+mod tests {
+    use super::CaseFilterer;
+    use crate::cases::rust_test_case::RustTestCase;
+    use crate::cases::CaseType;
+
+    struct Cases(Vec<RustTestCase>);
+
+    impl CaseFilterer<RustTestCase> for Cases {
+        fn is_focus_run(&self) -> bool {
+            false
+        }
+        fn set_focus_run(&mut self, _is_focus_run: bool) {}
+        fn get_cases(&self) -> &Vec<RustTestCase> {
+            &self.0
+        }
+        fn get_cases_mut(&mut self) -> &mut Vec<RustTestCase> {
+            &mut self.0
+        }
+    }
+
+    fn test_case(module_path: &'static str, name: &'static str, line: u32) -> RustTestCase {
+        RustTestCase {
+            name,
+            file: "src/itest.rs",
+            module_path,
+            skipped: false,
+            skip_reason: None,
+            skip_condition: None,
+            focused: false,
+            keyword: None,
+            tags: &[],
+            scene_path: None,
+            scene: None,
+            xfail: None,
+            retries: 0,
+            line,
+            function: |_| {},
+            setup_function: None,
+            teardown_function: None,
+        }
+    }
+
+    #[test]
+    fn unique_names_are_accepted() {
+        let cases = Cases(vec![
+            test_case("tests::itest", "first", 10),
+            test_case("tests::itest", "second", 20),
+            test_case("tests::other", "first", 30),
+        ]);
+        assert!(cases.check_duplicates(CaseType::RustTest).is_ok());
+    }
+
+    #[test]
+    fn duplicated_names_are_listed_with_locations() {
+        let cases = Cases(vec![
+            test_case("tests::itest", "second", 20),
+            test_case("tests::itest", "first", 10),
+            test_case("tests::other", "first", 30),
+            test_case("tests::itest", "second", 40),
+            test_case("tests::itest", "first", 50),
+            test_case("tests::itest", "first", 60),
+        ]);
+        let error = cases.check_duplicates(CaseType::RustTest).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "found Rust tests registered with duplicated names:\n  \
+            * tests::itest::first: src/itest.rs:10, src/itest.rs:50, src/itest.rs:60\n  \
+            * tests::itest::second: src/itest.rs:20, src/itest.rs:40"
+        );
+    }
+}
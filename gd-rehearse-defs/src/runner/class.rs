@@ -8,26 +8,35 @@ use godot::obj::WithBaseField;
 use godot::prelude::{godot_api, Base, GString, GodotClass, INode, Node, PackedStringArray};
 
 use crate::cases::rust_bench::{BenchContext, BenchError, RustBenchmark};
-use crate::cases::rust_test_case::{RustTestCase, TestContext};
+use crate::cases::rust_test_case::{RustTestCase, TestContext, TestError};
 use crate::cases::{Case, CaseOutcome, CaseType};
 
 use crate::registry::bench::{BenchResult, GdBenchmarks};
 use crate::registry::itest::{GdRustItests, TestResult};
 use crate::registry::CaseFilterer;
 
-use super::config::RunnerConfig;
+use super::config::{RunnerConfig, RunnerInfo, Shard};
 use super::extract_file_subtitle;
+use super::isolation::CaseIsolation;
 use super::panic::UnwindError;
 use super::print::MessageWriter;
+use super::scenes;
+use super::tests_run::{RunningTest, RustTestsRun, TestProgress};
 
 use std::time::{Duration, Instant};
 
-#[derive(Copy, Clone, Debug, Default)]
+/// Prefix of the lines listing cases with `--list-cases`, parsed by `cargo rehearse` to run every case in separate process.
+pub(crate) const LIST_CASES_PREFIX: &str = "gd-rehearse-case";
+
+#[derive(Clone, Debug, Default)]
 pub(crate) struct RunnerSummary {
     kind: CaseType,
     total: i64,
     passed: i64,
     skipped: i64,
+    expected_failures: Vec<String>,
+    unexpected_passes: Vec<String>,
+    flaky: Vec<String>,
 }
 
 impl RunnerSummary {
@@ -50,70 +59,138 @@ impl RunnerSummary {
         self.skipped += 1;
     }
 
-    fn update_stats(&mut self, test: &impl Case, outcome: &CaseOutcome, failed: &mut Vec<String>) {
+    fn update_stats(
+        &mut self,
+        test: &impl Case,
+        outcome: &CaseOutcome,
+        message: &Option<String>,
+        failed: &mut Vec<String>,
+    ) {
         self.inc_total();
+        let case_display = || {
+            let mut display = format!(
+                "{} ({}:{})",
+                test.get_case_qualified_name(),
+                extract_file_subtitle(test.get_case_file()),
+                test.get_case_line()
+            );
+            if let Some(message) = message {
+                display.push_str(&format!(" - {message}"));
+            }
+            display
+        };
         match outcome {
             CaseOutcome::Passed => self.inc_passed(),
-            CaseOutcome::Failed => failed.push(format!(
-                "{} > {}",
-                extract_file_subtitle(test.get_case_file()),
-                test.get_case_name()
-            )),
+            CaseOutcome::Failed => failed.push(case_display()),
             CaseOutcome::Skipped => self.inc_skipped(),
+            CaseOutcome::ExpectedFailure => self.expected_failures.push(case_display()),
+            CaseOutcome::UnexpectedPass => self.unexpected_passes.push(case_display()),
+            CaseOutcome::Flaky => {
+                self.inc_passed();
+                self.flaky.push(case_display());
+            }
         }
     }
 
-    fn conclude(&self, run_time: Duration, failed_list: &mut Vec<String>) -> bool {
+    /// Reasons why the cases failed, for the combined summary of the scenes run with `--scenes`.
+    fn failure_reasons(&self, failed_list: &[String], shard: Option<Shard>) -> Vec<String> {
+        if self.total - self.skipped == 0 && shard.is_none() {
+            return vec![format!("no {} cases were run", self.kind.for_summary())];
+        }
+        failed_list
+            .iter()
+            .cloned()
+            .chain(
+                self.unexpected_passes
+                    .iter()
+                    .map(|case| format!("unexpectedly passed: {case}")),
+            )
+            .collect()
+    }
+
+    fn conclude(
+        &mut self,
+        run_time: Duration,
+        failed_list: &mut Vec<String>,
+        shard: Option<Shard>,
+    ) -> bool {
         let Self {
             kind,
             total,
             passed,
             skipped,
+            ..
         } = *self;
+        let expected_failures = self.expected_failures.len() as i64;
+        let unexpected_passes = self.unexpected_passes.len() as i64;
+        let flaky = self.flaky.len() as i64;
 
         let writer = MessageWriter::new(false);
 
         let kind_display = kind.for_summary();
 
-        // Consider 0 cases run as a failure too, because it's probably a problem with the run itself.
+        // Consider 0 cases run as a failure too, because it's probably a problem with the run itself. Small sets of cases can be
+        // split into shards with no cases though.
         if total - skipped == 0 {
+            if let Some(shard) = shard {
+                writer.println(&format!("{kind_display}: 0 cases in shard {shard}."));
+                return true;
+            }
             writer.println(&format!("No {kind_display} cases were run. If this is intended, configure GdTestRunner to omit these cases."));
             return false;
         }
 
-        let failed = total - passed - skipped;
-        let all_passed = failed == 0;
+        let failed = total - passed - skipped - expected_failures - unexpected_passes;
+        let all_passed = failed == 0 && unexpected_passes == 0;
 
         let outcome = CaseOutcome::from_bool(all_passed);
 
         let run_time = (run_time.as_secs_f32() * 100.).round() / 100.;
 
-        let extra = if skipped > 0 {
-            format!(", {skipped} skipped")
-        } else {
-            "".to_string()
-        };
+        let mut extra = String::new();
+        if skipped > 0 {
+            extra.push_str(&format!(", {skipped} skipped"));
+        }
+        if expected_failures > 0 {
+            extra.push_str(&format!(", {expected_failures} expected failures"));
+        }
+        if unexpected_passes > 0 {
+            extra.push_str(&format!(", {unexpected_passes} unexpected passes"));
+        }
+        if flaky > 0 {
+            extra.push_str(&format!(", {flaky} flaky"));
+        }
 
         writer.println(&format!(
             "{kind_display} result: {outcome} {passed} passed; {failed} failed{extra}. Elapsed: {run_time:.2}s."
         ));
 
-        if !all_passed {
-            writer.println("\n  Failed:");
-            let max = 10;
-            for test in failed_list.iter().take(max) {
-                writer.println(&format!("  * {test}"));
-            }
+        Self::print_case_list(&writer, "Failed", failed_list);
+        Self::print_case_list(&writer, "Unexpectedly passed", &mut self.unexpected_passes);
+        Self::print_case_list(&writer, "Expected failures", &mut self.expected_failures);
+        Self::print_case_list(&writer, "Flaky", &mut self.flaky);
 
-            if failed_list.len() > max {
-                writer.println(&format!("  * ... and {} more.", failed_list.len() - max));
-            }
+        all_passed
+    }
 
-            failed_list.clear();
+    fn print_case_list(writer: &MessageWriter, header: &str, list: &mut Vec<String>) {
+        if list.is_empty() {
+            return;
+        }
 
-            writer.println("\n");
+        writer.println(&format!("\n  {header}:"));
+        let max = 10;
+        for test in list.iter().take(max) {
+            writer.println(&format!("  * {test}"));
         }
-        all_passed
+
+        if list.len() > max {
+            writer.println(&format!("  * ... and {} more.", list.len() - max));
+        }
+
+        list.clear();
+
+        writer.println("\n");
     }
 }
 
@@ -121,6 +198,9 @@ impl RunnerSummary {
 ///
 /// Runs functions annotated with `#[gditest]` and `#[gdbench]` macros, facilitating the testing and benchmarking of methods and functions that involve calls between Rust and Godot. To utilize it, create a scene in the Godot project associated with your `gdext`-based GDExtension and run the scene either from the command line or directly from the Godot editor.
 ///
+/// Cases are identified by their qualified names, which need to be unique. If the same name is registered for more than one test or benchmark,
+/// the run fails before executing any case, listing the conflicting locations.
+///
 /// ## Godot Properties
 ///
 /// `GdTestRunner` exposes some settable Godot-exported properties that customize its behavior:
@@ -133,11 +213,45 @@ impl RunnerSummary {
 ///   assessed, but only in the context of this `keyword`.
 /// - `ignore_keywords`: If set, all tests and benchmarks will be executed regardless of their set `keyword`.
 /// - `disallow_focus`: If set, the `focus` attribute of tests and benchmarks will be ignored.
-/// - `disallow_skip`: If set, the `skip` attribute of tests and benchmarks will be ignored.
-/// - `test_filters`: An array of strings tested against the names of tests and benchmarks. Those with names containing at least one of the specified
-///   filters will be executed.
+/// - `disallow_skip`: If set, the `skip` attribute of tests and benchmarks will be ignored. Conditions set with `skip_if` are still respected.
+/// - `test_filters`: An array of strings tested against the qualified names (`module::path::case_name`) of tests and benchmarks. Those with
+///   qualified names containing at least one of the specified filters will be executed.
+/// - `test_tags`: An array of tags tested against the `tags` of tests and benchmarks. Those with at least one of the specified tags will be executed.
+/// - `exclude_tags`: An array of tags. Tests and benchmarks with at least one of the specified tags won't be executed.
+/// - `match_all_tags`: If set, only tests and benchmarks with all of the tags specified in `test_tags` will be executed.
+/// - `test_retries`: Number of times a failing test will be rerun before being considered failed. Tests which pass after being rerun are
+///   reported as flaky. Tests with larger `retries` attribute use their own value.
+/// - `shuffle`: If set, tests and benchmarks are executed in random order. Seed used for shuffling is printed at the start of the run.
+/// - `isolate_cases`: If set, every test and benchmark gets its own temporary root node, added as a child of the runner and freed after
+///   the case. `scene_tree()` and `get_node()` of the case context resolve relative to it, while nodes of `gdsuite!` fixtures stay
+///   under the runner and are retrieved with `get_suite_node()`. Nodes added directly to the runner during the case are freed too,
+///   and fail the case.
 /// - `only_scene_path`: If `true`, runner will execute only tests for its scene path specified in their `scene_path` attribute.
 ///
+/// ## Configuration File
+///
+/// Options shared by every scene and developer can be set in `res://gd_rehearse.cfg` file, in Godot's `ConfigFile` format. Keys of its
+/// `[runner]` section are the [command line arguments](#command-line-arguments) in snake case, without leading dashes:
+///
+/// ```ini
+/// [runner]
+/// filters = ["physics", "signals"]
+/// keyword = "ci"
+/// retries = 2
+/// shuffle = true
+/// ```
+///
+/// ## Environment Variables
+///
+/// Options can also be set with `GD_REHEARSE_*` environment variables, named after the command line arguments in screaming snake case,
+/// eg. `GD_REHEARSE_FILTERS=physics,signals` or `GD_REHEARSE_KEYWORD=ci`. Flags are set with `true` or `1` and turned off with `false`
+/// or `0`, eg. `GD_REHEARSE_QUIET=1` (same as `GD_REHEARSE_QUIET_RUN=1`). Blank variables are ignored, and invalid values fail the run
+/// like invalid command line arguments.
+///
+/// Options are applied in order of increasing precedence, printed at the start of the run: configuration file, node properties,
+/// environment variables, command line arguments. Node properties override the file only when they differ from their default values,
+/// so flags set in the file are turned off with `false` value of the environment variable or command line argument, eg. `--shuffle=false`.
+///
 /// ## Command Line Arguments
 ///
 /// `GdTestRunner` is also suitable for running from the command line, as part of the Godot binary execution in headless mode:
@@ -146,14 +260,43 @@ impl RunnerSummary {
 /// godot_executable --path path/to/godot/project [scene path] --headless -- [optional arguments]
 /// ```
 /// - `scene_path`: An optional path to the scene with the `GdTestRunner` object. Not needed if the testing scene is the main scene of the project.
-/// - `optional arguments`: Options such as:
+/// - `optional arguments`: Options such as the ones below. Flags can also be given `true` or `false` value, eg. `--isolate=false`, turning
+///   off the flag set by the sources of lower precedence. Lists can be given empty value, eg. `--exclude-tags=`, clearing the list set
+///   by the sources of lower precedence.
 ///   - `--rust-test` or `--rust-benchmarks`: If at least one is selected, overwrites the analogous properties and runs only the specified element.
 ///   - `--disallow-focus` or `--allow-focus`: Overwrites the `disallow_focus` property.
 ///   - `--disallow-skip` or `--disallow-focus`: Overwrites the `disallow_skip` property.
 ///   - `--mute-keyword` or `--keyword=my_keyword`: Either mutes the `test_keyword` property or replaces it with the specified one.
 ///   - `--ignore-keywords`: Replaces the `ignore_keywords` property.
 ///   - `--mute-filters` or `--filters=[filter1,filter2]`: Either mutes the `test_filters` property or replaces it with the specified filters.
+///   - `--tags=[tag1,tag2]`: Replaces the `test_tags` property with the specified tags.
+///   - `--exclude-tags=[tag1,tag2]`: Replaces the `exclude_tags` property with the specified tags.
+///   - `--match-all-tags`: Sets `match_all_tags` property with `true`.
+///   - `--retries=N`: Replaces the `test_retries` property.
+///   - `--shuffle`: Sets `shuffle` property with `true`.
+///   - `--shuffle-seed=N`: Shuffles the cases using the specified seed, reproducing the order of some previous run.
+///   - `--isolate`: Sets `isolate_cases` property with `true`.
+///   - `--update-snapshots`: Snapshot assertions write the current values to the snapshot files instead of comparing them.
+///   - `--prop-seed=N`: Property-based tests use the specified seed, reproducing the failure of some previous run.
+///   - `--prop-iterations=N`: Number of inputs checked by property-based tests, greater than 0. 100 by default.
+///   - `--shard=index/count`: Splits the cases into `count` parts and runs only the one with specified 1-based `index`, eg. `--shard=2/4`.
+///     Every case is always assigned to the same shard, regardless of other cases being added or removed. Shard without any tests or
+///     benchmarks to run doesn't fail the run, in contrast to the runs without sharding.
 ///   - `--only-scene-path`: Sets `only_scene_path` property with `true`
+///   - `--only-case=module::path::case_name`: Runs only the case with specified qualified name, after all other filters are applied.
+///   - `--only-file=path/to/file.rs`: Runs only the cases declared in the specified file, as listed by `--list-cases`.
+///   - `--files=[path1,path2]`: Runs only the cases declared in the specified files. Paths are matched against the ends of the case
+///     files, so they can be relative to the crate or workspace directory. Used by `cargo rehearse --watch` to rerun cases from the
+///     changed files.
+///   - `--scenes=[res://a.tscn,res://b.tscn]`: Runs the specified scenes one after another, each with its own `GdTestRunner` root and
+///     properties, printing the combined summary at the end. Exit code is `0` only if cases of every scene passed. Combined with
+///     `only_scene_path` property of the scenes, runs the cases bound to every scene with their `scene_path` attribute. Cannot be
+///     combined with `--list-cases`, `--only-case` and `--only-file`, so `cargo rehearse --process-per-case` needs to run every scene
+///     separately.
+///   - `--list-cases`: Prints the cases selected to run instead of running them, one per line, in tab-separated format:
+///     `gd-rehearse-case`, `test` or `bench`, qualified name and file, followed by `skipped` for the cases which would be skipped.
+///     Used by `cargo rehearse --process-per-case` to run every case in separate Godot process, so a crashing case doesn't stop the
+///     whole run.
 ///
 #[derive(GodotClass)]
 #[class(base=Node)]
@@ -173,12 +316,26 @@ pub struct GdTestRunner {
     #[export]
     test_filters: PackedStringArray,
     #[export]
+    test_tags: PackedStringArray,
+    #[export]
+    exclude_tags: PackedStringArray,
+    #[export]
+    match_all_tags: bool,
+    #[export]
+    test_retries: u32,
+    #[export]
+    shuffle: bool,
+    #[export]
+    isolate_cases: bool,
+    #[export]
     only_scene_path: bool,
     tests_summary: RunnerSummary,
     benches_summary: RunnerSummary,
     config: RunnerConfig,
     failed_list: Vec<String>,
     began_run: bool,
+    rust_tests_run: Option<RustTestsRun>,
+    rust_bench_handler: Option<GdBenchmarks>,
     base: Base<Node>,
 }
 
@@ -191,6 +348,12 @@ impl INode for GdTestRunner {
             test_filters: PackedStringArray::new(),
             test_keyword: GString::new(),
             ignore_keywords: false,
+            test_tags: PackedStringArray::new(),
+            exclude_tags: PackedStringArray::new(),
+            match_all_tags: false,
+            test_retries: 0,
+            shuffle: false,
+            isolate_cases: false,
             run_benchmarks: true,
             run_tests: true,
             only_scene_path: false,
@@ -199,6 +362,8 @@ impl INode for GdTestRunner {
             config: RunnerConfig::default(),
             failed_list: Vec::new(),
             began_run: false,
+            rust_tests_run: None,
+            rust_bench_handler: None,
             base,
         }
     }
@@ -213,6 +378,10 @@ impl INode for GdTestRunner {
 impl GdTestRunner {
     #[func]
     fn test_run(&mut self) {
+        if let Some(run) = self.rust_tests_run.take() {
+            self.continue_rust_tests(run);
+            return;
+        }
         if self.began_run {
             return;
         }
@@ -232,9 +401,22 @@ impl GdTestRunner {
             self.only_scene_path,
             path,
             &self.test_filters,
+            &self.test_tags,
+            &self.exclude_tags,
+            self.match_all_tags,
+            self.test_retries,
+            self.shuffle,
+            self.isolate_cases,
             false,
         ) {
-            Ok(config) => self.config = config,
+            Ok(config) => {
+                crate::snapshot::set_update_snapshots(config.update_snapshots());
+                crate::property::set_property_settings(
+                    config.property_seed(),
+                    config.property_iterations(),
+                );
+                self.config = config
+            }
             Err(error) => {
                 writer.println(&error.to_string());
                 self.end(1);
@@ -242,15 +424,20 @@ impl GdTestRunner {
             }
         }
 
+        // Launched scene only begins the sequence of scenes to run.
+        if !self.config.scenes().is_empty() && !scenes::in_sequence() {
+            if let Some(outcome) = scenes::begin(&self.base().clone(), self.config.scenes()) {
+                self.end(!outcome as i32);
+            }
+            return;
+        }
+
         let writer = MessageWriter::new(self.config.is_quiet());
 
         writer.print_begin();
 
         writer.print_summary_info(&self.config);
 
-        let mut rust_test_outcome = true;
-        let mut rust_bench_outcome = true;
-
         let mut rust_tests_handler: Option<GdRustItests> = None;
         let mut rust_bench_handler: Option<GdBenchmarks> = None;
 
@@ -258,21 +445,33 @@ impl GdTestRunner {
 
         // Gather tests and benches.
         if self.config.run_rust_tests() {
-            let handler = GdRustItests::init();
-            rust_tests_handler = Some(handler);
+            match GdRustItests::init() {
+                Ok(handler) => rust_tests_handler = Some(handler),
+                Err(error) => {
+                    MessageWriter::new(false).println(&error.to_string());
+                    self.end(1);
+                    return;
+                }
+            }
         }
 
         if self.config.run_rust_benchmarks() {
-            let handler = GdBenchmarks::init();
-            rust_bench_handler = Some(handler);
+            match GdBenchmarks::init() {
+                Ok(handler) => rust_bench_handler = Some(handler),
+                Err(error) => {
+                    MessageWriter::new(false).println(&error.to_string());
+                    self.end(1);
+                    return;
+                }
+            }
         }
 
         // Filter tests and benches on path and focus
         if let Some(handler) = &mut rust_tests_handler {
-            handler.filter_path_keyword(&self.config);
+            handler.filter_path_keyword_tags(&self.config);
         }
         if let Some(handler) = &mut rust_bench_handler {
-            handler.filter_path_keyword(&self.config);
+            handler.filter_path_keyword_tags(&self.config);
         }
 
         // Filter tests and benches on focus and filter
@@ -280,34 +479,70 @@ impl GdTestRunner {
             handler.set_focus_run(is_focus_run);
             handler.filter_focus_filters(&self.config);
             is_focus_run = handler.is_focus_run();
-            handler.finish_setup();
+            handler.finish_setup(&self.config);
             writer.println(&handler.get_post_init_summary());
         }
         if let Some(handler) = &mut rust_bench_handler {
             handler.set_focus_run(is_focus_run);
             handler.filter_focus_filters(&self.config);
-            handler.finish_setup();
+            handler.finish_setup(&self.config);
             // is_focus_run = handler.is_focus_run();
             writer.println(&handler.get_post_init_summary());
         }
 
+        if self.config.list_cases() {
+            self.print_selected_cases(rust_tests_handler.as_ref(), rust_bench_handler.as_ref());
+            self.end(0);
+            return;
+        }
+
+        self.rust_bench_handler = rust_bench_handler;
+
         // Run Rust Tests.
-        if let Some(mut handler) = rust_tests_handler {
+        if let Some(handler) = rust_tests_handler {
             writer.println("");
             writer.print_horizontal_separator();
             writer.println("   Running Rust tests");
             writer.print_horizontal_separator();
+            writer.println("");
 
-            let clock = Instant::now();
-            self.run_rust_tests(&mut handler);
-            let run_time = clock.elapsed();
+            let info = RunnerInfo::gather(&self.config);
+            self.continue_rust_tests(RustTestsRun::new(handler, info));
+        } else {
+            self.finish_run(true);
+        }
+    }
 
-            writer.println("");
-            rust_test_outcome = self.tests_summary.conclude(run_time, &mut self.failed_list);
+    // One line for every case: prefix, kind, qualified name and file, separated with tabs. Cases which would be skipped are marked
+    // with additional `skipped` field.
+    fn print_selected_cases(&self, tests: Option<&GdRustItests>, benches: Option<&GdBenchmarks>) {
+        let info = RunnerInfo::gather(&self.config);
+        for test in tests.iter().flat_map(|handler| handler.get_cases()) {
+            self.print_selected_case("test", test, &info);
+        }
+        for bench in benches.iter().flat_map(|handler| handler.get_cases()) {
+            self.print_selected_case("bench", bench, &info);
+        }
+    }
+
+    fn print_selected_case(&self, kind: &str, case: &impl Case, info: &RunnerInfo) {
+        let mut line = format!(
+            "{LIST_CASES_PREFIX}\t{kind}\t{}\t{}",
+            case.get_case_qualified_name(),
+            case.get_case_file()
+        );
+        if !case.should_run_skip(self.config.disallow_skip(), info) {
+            line.push_str("\tskipped");
         }
+        MessageWriter::new(false).println(&line);
+    }
+
+    fn finish_run(&mut self, rust_test_outcome: bool) {
+        let writer = MessageWriter::new(self.config.is_quiet());
+        let mut rust_bench_outcome = true;
 
         // Run Rust Benchmarks.
-        if let (Some(mut handler), true) = (rust_bench_handler, rust_test_outcome) {
+        if let (Some(mut handler), true) = (self.rust_bench_handler.take(), rust_test_outcome) {
             writer.println("");
             writer.print_horizontal_separator();
             writer.println("   Running Rust benchmarks");
@@ -318,12 +553,27 @@ impl GdTestRunner {
             let run_time = clock.elapsed();
 
             writer.println("");
-            rust_bench_outcome = self
-                .benches_summary
-                .conclude(run_time, &mut self.failed_list);
+            scenes::record_failures(
+                self.config.scene_path(),
+                &self
+                    .benches_summary
+                    .failure_reasons(&self.failed_list, self.config.shard()),
+            );
+            rust_bench_outcome =
+                self.benches_summary
+                    .conclude(run_time, &mut self.failed_list, self.config.shard());
         }
 
-        let outcome = rust_test_outcome && rust_bench_outcome;
+        let mut outcome = rust_test_outcome && rust_bench_outcome;
+
+        if scenes::in_sequence() {
+            let scene = self.config.scene_path().to_owned();
+            let Some(combined) = scenes::finish_scene(&self.base().clone(), &scene, outcome) else {
+                // Changed into the next scene.
+                return;
+            };
+            outcome = combined;
+        }
 
         if outcome {
             writer.print_success()
@@ -344,39 +594,161 @@ impl GdTestRunner {
             .done();
     }
 
-    fn run_rust_tests(&mut self, handler: &mut GdRustItests) {
-        let ctx = TestContext::new(self.base().clone());
-
+    /// Runs the tests until all are finished, or some test is suspended to wait for physics ticks. In that case, the run is continued
+    /// on the next physics frames.
+    fn continue_rust_tests(&mut self, mut run: RustTestsRun) {
         let writer = MessageWriter::new(self.config.is_quiet());
-        writer.println("");
 
-        let mut last_file = None;
-        while let Some(test) = handler.get_test() {
-            writer.print_test_pre(test, &mut last_file);
+        if let Some(running) = run.current.take() {
+            let test = running.test;
+            match self.tick_rust_test(running) {
+                TestProgress::Suspended(running) => {
+                    run.current = Some(running);
+                    self.rust_tests_run = Some(run);
+                    return;
+                }
+                TestProgress::Finished(result) => self.conclude_rust_test(&mut run, &test, result),
+            }
+        }
 
-            let result = self.run_rust_test(&test, &ctx);
+        while let Some(test) = run.handler.get_test() {
+            writer.print_test_pre(test, &mut run.last_module);
+
+            match self.begin_rust_test(&test, &mut run) {
+                TestProgress::Suspended(running) => {
+                    run.current = Some(running);
+                    self.rust_tests_run = Some(run);
+                    return;
+                }
+                TestProgress::Finished(result) => self.conclude_rust_test(&mut run, &test, result),
+            }
+        }
+
+        let run_time = run.clock.elapsed();
+        writer.println("");
+        scenes::record_failures(
+            self.config.scene_path(),
+            &self
+                .tests_summary
+                .failure_reasons(&self.failed_list, self.config.shard()),
+        );
+        let rust_test_outcome =
             self.tests_summary
-                .update_stats(&test, &result.outcome, &mut self.failed_list);
-            writer.print_test_post(test.name, result);
+                .conclude(run_time, &mut self.failed_list, self.config.shard());
+        self.finish_run(rust_test_outcome);
+    }
+
+    fn conclude_rust_test(
+        &mut self,
+        run: &mut RustTestsRun,
+        test: &RustTestCase,
+        mut result: TestResult,
+    ) {
+        // Failure of `after_all` is reported on the last case of the suite, along with its own failure.
+        if let Some(Err(err)) = run.handler.get_suite_run(test).map(|suite| suite.leave()) {
+            result = result.fail_also(err);
         }
+        self.tests_summary.update_stats(
+            test,
+            &result.outcome,
+            &result.message,
+            &mut self.failed_list,
+        );
+        MessageWriter::new(self.config.is_quiet()).print_test_post(test.name, result);
     }
 
-    fn run_rust_test(&self, test: &RustTestCase, ctx: &TestContext) -> TestResult {
-        if !test.should_run_skip(self.config.disallow_skip()) {
-            return TestResult::skipped();
+    fn begin_rust_test(&self, test: &RustTestCase, run: &mut RustTestsRun) -> TestProgress {
+        if !test.should_run_skip(self.config.disallow_skip(), &run.info) {
+            return TestProgress::Finished(TestResult::skipped(test.get_skip_message()));
         }
 
-        let result = super::panic::handle_panic(|| (test.function)(ctx));
+        if let Some(suite) = run.handler.get_suite_run(test) {
+            if let Err(err) = suite.enter(&self.base()) {
+                return TestProgress::Finished(TestResult::failed(err));
+            }
+        }
 
-        if let Err(err) = result {
-            TestResult::failed(err)
-        } else {
-            TestResult::success()
+        self.begin_rust_test_attempt(*test, 1)
+    }
+
+    fn begin_rust_test_attempt(&self, test: RustTestCase, attempts: u32) -> TestProgress {
+        let isolation = self
+            .config
+            .isolate()
+            .then(|| CaseIsolation::new(self.base().clone()));
+        let scene_tree = isolation
+            .as_ref()
+            .map_or_else(|| self.base().clone(), CaseIsolation::root);
+        let mut ctx = TestContext::new(scene_tree);
+        ctx.set_suite_tree(self.base().clone());
+        let result = test.start(&mut ctx);
+
+        let running = Box::new(RunningTest {
+            test,
+            ctx,
+            isolation,
+            attempts,
+            pending: None,
+        });
+        self.continue_rust_test(running, result)
+    }
+
+    /// Counts down physics ticks of the suspended test, resuming it after the last one.
+    fn tick_rust_test(&self, mut running: Box<RunningTest>) -> TestProgress {
+        let Some(mut pending) = running.pending.take() else {
+            return self.continue_rust_test(running, Ok(()));
+        };
+        pending.ticks = pending.ticks.saturating_sub(1);
+        if pending.ticks > 0 {
+            running.pending = Some(pending);
+            return TestProgress::Suspended(running);
+        }
+        let result = running.test.resume(&running.ctx, pending.continuation);
+        self.continue_rust_test(running, result)
+    }
+
+    fn continue_rust_test(
+        &self,
+        mut running: Box<RunningTest>,
+        mut result: Result<(), TestError>,
+    ) -> TestProgress {
+        while let (Ok(()), Some(pending)) = (&result, running.ctx.take_pending_ticks()) {
+            if pending.ticks > 0 {
+                running.pending = Some(pending);
+                return TestProgress::Suspended(running);
+            }
+            result = running.test.resume(&running.ctx, pending.continuation);
         }
+
+        let RunningTest {
+            test,
+            mut ctx,
+            isolation,
+            attempts,
+            ..
+        } = *running;
+        let mut result = test.finish(&mut ctx, result);
+        if let Some(isolation) = isolation {
+            result = result.and(isolation.finish().map_err(TestError::Isolation));
+        }
+
+        // Expected failures aren't retried, as their failure is the desired outcome.
+        let retries = test.retries.max(self.config.retries());
+        TestProgress::Finished(match (result, test.xfail) {
+            (Err(_), Some(reason)) => TestResult::expected_failure(reason),
+            (Ok(()), Some(reason)) => TestResult::unexpected_pass(reason),
+            (Err(_), None) if attempts <= retries => {
+                return self.begin_rust_test_attempt(test, attempts + 1)
+            }
+            (Err(err), None) => TestResult::failed(err),
+            (Ok(()), None) if attempts > 1 => TestResult::flaky(attempts),
+            (Ok(()), None) => TestResult::success(),
+        })
     }
 
     fn run_rust_benchmarks(&mut self, benchmarks: &mut GdBenchmarks) {
         let mut ctx = BenchContext::new(self.base().clone());
+        let info = RunnerInfo::gather(&self.config);
 
         let writer = MessageWriter::new(self.config.is_quiet());
 
@@ -386,21 +758,46 @@ impl GdTestRunner {
         }
         writer.println(&first_line);
 
-        let mut last_file = None;
+        let mut last_module = None;
         while let Some(bench) = benchmarks.get_benchmark() {
-            writer.print_bench_pre(&bench, &mut last_file);
-
-            let result = self.run_rust_benchmark(&bench, &mut ctx);
+            writer.print_bench_pre(&bench, &mut last_module);
+
+            let isolation = self
+                .config
+                .isolate()
+                .then(|| CaseIsolation::new(self.base().clone()));
+            let mut isolated_ctx;
+            let case_ctx = if let Some(isolation) = &isolation {
+                isolated_ctx = BenchContext::new(isolation.root());
+                &mut isolated_ctx
+            } else {
+                &mut ctx
+            };
+
+            let mut result = self.run_rust_benchmark(&bench, case_ctx, &info);
+            case_ctx.free_instantiated_scenes();
+            if let Some(Err(err)) = isolation.map(CaseIsolation::finish) {
+                result = result.fail_also(BenchError::Isolation(err));
+            }
 
-            self.benches_summary
-                .update_stats(&bench, &result.outcome, &mut self.failed_list);
+            self.benches_summary.update_stats(
+                &bench,
+                &result.outcome,
+                &result.message,
+                &mut self.failed_list,
+            );
             writer.print_bench_post(bench.get_case_name(), result);
         }
     }
 
-    fn run_rust_benchmark(&self, bench: &RustBenchmark, ctx: &mut BenchContext) -> BenchResult {
-        if !bench.should_run_skip(self.config.disallow_skip()) {
-            return BenchResult::skipped();
+    fn run_rust_benchmark(
+        &self,
+        bench: &RustBenchmark,
+        ctx: &mut BenchContext,
+        info: &RunnerInfo,
+    ) -> BenchResult {
+        if !bench.should_run_skip(self.config.disallow_skip(), info) {
+            return BenchResult::skipped(bench.get_skip_message());
         }
 
         match bench.execute_setup_function(ctx.clone()) {
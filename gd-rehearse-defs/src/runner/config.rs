@@ -6,7 +6,17 @@
 
 use super::{is_godot_debug, is_headless_run, is_rust_debug};
 use core::fmt;
-use godot::builtin::{GString, PackedStringArray};
+use godot::builtin::{GString, PackedStringArray, Variant, VariantArray, VariantType};
+use godot::classes::{ConfigFile, FileAccess};
+use godot::global::Error;
+use godot::obj::NewGd;
+
+/// Path of the optional project-level configuration file, with options applied to every run.
+pub(crate) const CONFIG_FILE_PATH: &str = "res://gd_rehearse.cfg";
+/// Section of the configuration file containing the options.
+const CONFIG_FILE_SECTION: &str = "runner";
+/// Prefix of the environment variables with options, eg. `GD_REHEARSE_FILTERS` for `--filters`.
+const ENV_PREFIX: &str = "GD_REHEARSE_";
 
 #[derive(Debug)]
 pub struct ConfigError {
@@ -27,19 +37,35 @@ impl fmt::Display for ConfigError {
 
 #[derive(Default)]
 pub(crate) struct CliConfig {
-    disallow_focus: bool,
-    allow_focus: bool,
-    disallow_skip: bool,
-    allow_skip: bool,
-    mute_keyword: bool,
-    ignore_keywords: bool,
-    mute_filters: bool,
-    run_rust_tests: bool,
-    run_rust_benchmarks: bool,
-    only_scene_path: bool,
+    disallow_focus: Option<bool>,
+    allow_focus: Option<bool>,
+    disallow_skip: Option<bool>,
+    allow_skip: Option<bool>,
+    mute_keyword: Option<bool>,
+    ignore_keywords: Option<bool>,
+    mute_filters: Option<bool>,
+    run_rust_tests: Option<bool>,
+    run_rust_benchmarks: Option<bool>,
+    only_scene_path: Option<bool>,
     keyword: String,
-    filters: Vec<String>,
-    quiet_run: bool,
+    filters: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+    exclude_tags: Option<Vec<String>>,
+    match_all_tags: Option<bool>,
+    retries: Option<u32>,
+    shuffle: Option<bool>,
+    shuffle_seed: Option<u64>,
+    shard: Option<Shard>,
+    only_case: Option<String>,
+    only_file: Option<String>,
+    files: Option<Vec<String>>,
+    list_cases: Option<bool>,
+    scenes: Option<Vec<String>>,
+    isolate: Option<bool>,
+    update_snapshots: Option<bool>,
+    property_seed: Option<u64>,
+    property_iterations: Option<u32>,
+    quiet_run: Option<bool>,
 }
 
 impl CliConfig {
@@ -54,66 +80,225 @@ impl CliConfig {
     pub const CMD_USER_MUTE_FILTERS: &'static str = "--mute-filters";
     pub const CMD_USER_KEYWORD: &'static str = "--keyword";
     pub const CMD_USER_FILTERS: &'static str = "--filters";
+    pub const CMD_USER_TAGS: &'static str = "--tags";
+    pub const CMD_USER_EXCLUDE_TAGS: &'static str = "--exclude-tags";
+    pub const CMD_USER_MATCH_ALL_TAGS: &'static str = "--match-all-tags";
+    pub const CMD_USER_RETRIES: &'static str = "--retries";
+    pub const CMD_USER_SHUFFLE: &'static str = "--shuffle";
+    pub const CMD_USER_SHUFFLE_SEED: &'static str = "--shuffle-seed";
+    pub const CMD_USER_SHARD: &'static str = "--shard";
+    pub const CMD_USER_ONLY_CASE: &'static str = "--only-case";
+    pub const CMD_USER_ONLY_FILE: &'static str = "--only-file";
+    pub const CMD_USER_FILES: &'static str = "--files";
+    pub const CMD_USER_LIST_CASES: &'static str = "--list-cases";
+    pub const CMD_USER_SCENES: &'static str = "--scenes";
+    pub const CMD_USER_ISOLATE: &'static str = "--isolate";
+    pub const CMD_USER_UPDATE_SNAPSHOTS: &'static str = "--update-snapshots";
+    pub const CMD_USER_PROP_SEED: &'static str = "--prop-seed";
+    pub const CMD_USER_PROP_ITERATIONS: &'static str = "--prop-iterations";
     pub const CMD_USER_ONLY_SCENE_PATH: &'static str = "--only-scene-path";
     pub const CMD_USER_QUIET_RUN: &'static str = "--quiet-run";
 
     pub fn from_os() -> Result<Self, ConfigError> {
         let args = godot::classes::Os::singleton().get_cmdline_user_args();
-        let mut args_vec = args.as_slice().iter().collect::<Vec<_>>();
+        Self::from_args(args.as_slice())
+    }
+
+    /// Reads the options from [`CONFIG_FILE_PATH`], if it exists. Keys in its `[runner]` section are the command line arguments
+    /// in snake case, without leading dashes. Flags are set with `true` or `false`, lists with arrays of strings.
+    pub fn from_file() -> Result<Option<Self>, ConfigError> {
+        if !FileAccess::file_exists(CONFIG_FILE_PATH) {
+            return Ok(None);
+        }
+        let mut file = ConfigFile::new_gd();
+        let error = file.load(CONFIG_FILE_PATH);
+        if error != Error::OK {
+            return Err(ConfigError::new(format!(
+                "cannot load {CONFIG_FILE_PATH}: {error:?}"
+            )));
+        }
+
+        let mut args = Vec::new();
+        for section in file.get_sections().as_slice() {
+            if section.to_string() != CONFIG_FILE_SECTION {
+                return Err(ConfigError::new(format!(
+                    "unrecognized section [{section}] in {CONFIG_FILE_PATH}, options need to be in [{CONFIG_FILE_SECTION}] section"
+                )));
+            }
+            for key in file.get_section_keys(section).as_slice() {
+                let value = file.get_value(section, key);
+                args.push(Self::file_value_to_arg(&key.to_string(), &value)?);
+            }
+        }
+
+        Self::from_args(&args)
+            .map(Some)
+            .map_err(|error| ConfigError::new(format!("in {CONFIG_FILE_PATH}: {}", error.message)))
+    }
+
+    /// Reads the options from `GD_REHEARSE_*` environment variables, named after the command line arguments in screaming snake case,
+    /// eg. `GD_REHEARSE_FILTERS=a,b`. Flags are set with `true` or `false` (`1` or `0`), and `GD_REHEARSE_QUIET` is accepted for
+    /// `--quiet-run`. Blank variables, like the ones set from empty CI inputs, are ignored.
+    pub fn from_env() -> Result<Option<Self>, ConfigError> {
+        let mut variables = Vec::new();
+        for (name, value) in std::env::vars_os() {
+            let Some(name) = name.to_str().filter(|name| name.starts_with(ENV_PREFIX)) else {
+                continue;
+            };
+            let Some(value) = value.to_str() else {
+                return Err(ConfigError::new(format!(
+                    "value of environment variable {name} needs to be valid unicode"
+                )));
+            };
+            if !value.trim().is_empty() {
+                variables.push((name.to_owned(), value.to_owned()));
+            }
+        }
+        if variables.is_empty() {
+            return Ok(None);
+        }
+        variables.sort();
+
+        let args = variables
+            .iter()
+            .map(|(name, value)| Self::env_variable_to_arg(name, value))
+            .collect::<Vec<_>>();
+
+        Self::from_args(&args).map(Some).map_err(|error| {
+            ConfigError::new(format!(
+                "in {ENV_PREFIX}* environment variables: {}",
+                error.message
+            ))
+        })
+    }
+
+    // Flags accept values too, so every variable is passed as an argument with value, validated like the command line ones.
+    fn env_variable_to_arg(name: &str, value: &str) -> GString {
+        let mut arg = format!(
+            "--{}",
+            name[ENV_PREFIX.len()..].to_lowercase().replace('_', "-")
+        );
+        if arg == "--quiet" {
+            arg = Self::CMD_USER_QUIET_RUN.to_owned();
+        }
+        format!("{arg}={value}").into()
+    }
+
+    fn file_value_to_arg(key: &str, value: &Variant) -> Result<GString, ConfigError> {
+        let arg = format!("--{}", key.replace('_', "-"));
+        let value = match value.get_type() {
+            VariantType::BOOL
+            | VariantType::INT
+            | VariantType::STRING
+            | VariantType::STRING_NAME => {
+                value.stringify().to_string()
+            }
+            VariantType::ARRAY => value
+                .to::<VariantArray>()
+                .iter_shared()
+                .map(|item| item.stringify().to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            VariantType::PACKED_STRING_ARRAY => {
+                RunnerConfig::to_string_vec(&value.to::<PackedStringArray>()).join(",")
+            }
+            _ => {
+                return Err(ConfigError::new(format!(
+                    "value of `{key}` in {CONFIG_FILE_PATH} needs to be a bool, integer, string or array of strings"
+                )))
+            }
+        };
+        Ok(format!("{arg}={value}").into())
+    }
+
+    fn from_args(args: &[GString]) -> Result<Self, ConfigError> {
+        let mut args_vec = args.iter().collect::<Vec<_>>();
 
-        let run_rust_tests = Self::get_arg(&mut args_vec, Self::CMD_USER_RUST_TESTS);
-        let run_rust_benchmarks = Self::get_arg(&mut args_vec, Self::CMD_USER_RUST_BENCHMARKS);
+        let run_rust_tests = Self::get_flag(&mut args_vec, Self::CMD_USER_RUST_TESTS)?;
+        let run_rust_benchmarks = Self::get_flag(&mut args_vec, Self::CMD_USER_RUST_BENCHMARKS)?;
 
-        let allow_focus = Self::get_arg(&mut args_vec, Self::CMD_USER_ALLOW_FOCUS);
-        let disallow_focus = Self::get_arg(&mut args_vec, Self::CMD_USER_DISALLOW_FOCUS);
+        let allow_focus = Self::get_flag(&mut args_vec, Self::CMD_USER_ALLOW_FOCUS)?;
+        let disallow_focus = Self::get_flag(&mut args_vec, Self::CMD_USER_DISALLOW_FOCUS)?;
 
         Self::check_mutually_exclusive_args(
-            allow_focus,
-            disallow_focus,
+            allow_focus.is_some(),
+            disallow_focus.is_some(),
             Self::CMD_USER_ALLOW_FOCUS,
             Self::CMD_USER_DISALLOW_FOCUS,
         )?;
 
-        let allow_skip = Self::get_arg(&mut args_vec, Self::CMD_USER_ALLOW_SKIP);
-        let disallow_skip = Self::get_arg(&mut args_vec, Self::CMD_USER_DISALLOW_SKIP);
+        let allow_skip = Self::get_flag(&mut args_vec, Self::CMD_USER_ALLOW_SKIP)?;
+        let disallow_skip = Self::get_flag(&mut args_vec, Self::CMD_USER_DISALLOW_SKIP)?;
 
         Self::check_mutually_exclusive_args(
-            allow_skip,
-            disallow_skip,
+            allow_skip.is_some(),
+            disallow_skip.is_some(),
             Self::CMD_USER_ALLOW_SKIP,
             Self::CMD_USER_DISALLOW_SKIP,
         )?;
 
-        let mute_keyword = Self::get_arg(&mut args_vec, Self::CMD_USER_MUTE_KEYWORD);
-        let ignore_keywords = Self::get_arg(&mut args_vec, Self::CMD_USER_IGNORE_KEYWORDS);
+        let mute_keyword = Self::get_flag(&mut args_vec, Self::CMD_USER_MUTE_KEYWORD)?;
+        let ignore_keywords = Self::get_flag(&mut args_vec, Self::CMD_USER_IGNORE_KEYWORDS)?;
 
-        let keyword_arg = Self::get_arg_with_value(&mut args_vec, Self::CMD_USER_KEYWORD);
-        let keyword = if keyword_arg.is_empty() {
-            "".to_owned()
-        } else {
-            keyword_arg[0].to_owned()
-        };
+        let keyword =
+            Self::get_arg_with_value(&mut args_vec, Self::CMD_USER_KEYWORD)?.unwrap_or_default();
 
         Self::check_mutually_exclusive_args(
-            mute_keyword,
+            mute_keyword == Some(true),
             !keyword.is_empty(),
             Self::CMD_USER_MUTE_KEYWORD,
             Self::CMD_USER_KEYWORD,
         )?;
 
-        let mute_filters = Self::get_arg(&mut args_vec, Self::CMD_USER_MUTE_FILTERS);
-        let filters = Self::get_arg_with_value(&mut args_vec, Self::CMD_USER_FILTERS);
+        let mute_filters = Self::get_flag(&mut args_vec, Self::CMD_USER_MUTE_FILTERS)?;
+        let filters = Self::get_arg_with_list(&mut args_vec, Self::CMD_USER_FILTERS)?;
 
         Self::check_mutually_exclusive_args(
-            mute_filters,
-            !filters.is_empty(),
+            mute_filters == Some(true),
+            filters.is_some(),
             Self::CMD_USER_MUTE_FILTERS,
             Self::CMD_USER_FILTERS,
         )?;
 
-        let only_scene_path = Self::get_arg(&mut args_vec, Self::CMD_USER_ONLY_SCENE_PATH);
+        let tags = Self::get_arg_with_list(&mut args_vec, Self::CMD_USER_TAGS)?;
+        let exclude_tags = Self::get_arg_with_list(&mut args_vec, Self::CMD_USER_EXCLUDE_TAGS)?;
+        let match_all_tags = Self::get_flag(&mut args_vec, Self::CMD_USER_MATCH_ALL_TAGS)?;
+
+        let retries = Self::get_arg_with_number(&mut args_vec, Self::CMD_USER_RETRIES)?;
+
+        let shuffle = Self::get_flag(&mut args_vec, Self::CMD_USER_SHUFFLE)?;
+        let shuffle_seed = Self::get_arg_with_number(&mut args_vec, Self::CMD_USER_SHUFFLE_SEED)?;
 
-        let quiet_run = Self::get_arg(&mut args_vec, Self::CMD_USER_QUIET_RUN);
+        let shard = Self::get_arg_with_value(&mut args_vec, Self::CMD_USER_SHARD)?
+            .map(|shard| Shard::parse(&shard))
+            .transpose()?;
+
+        // Values aren't split, as commas are valid in file paths.
+        let only_case = Self::get_arg_with_value(&mut args_vec, Self::CMD_USER_ONLY_CASE)?;
+        let only_file = Self::get_arg_with_value(&mut args_vec, Self::CMD_USER_ONLY_FILE)?;
+        let files = Self::get_arg_with_list(&mut args_vec, Self::CMD_USER_FILES)?;
+        let list_cases = Self::get_flag(&mut args_vec, Self::CMD_USER_LIST_CASES)?;
+
+        let scenes = Self::get_arg_with_list(&mut args_vec, Self::CMD_USER_SCENES)?;
+
+        let isolate = Self::get_flag(&mut args_vec, Self::CMD_USER_ISOLATE)?;
+
+        let update_snapshots = Self::get_flag(&mut args_vec, Self::CMD_USER_UPDATE_SNAPSHOTS)?;
+
+        let property_seed = Self::get_arg_with_number(&mut args_vec, Self::CMD_USER_PROP_SEED)?;
+        let property_iterations =
+            Self::get_arg_with_number(&mut args_vec, Self::CMD_USER_PROP_ITERATIONS)?;
+        // No iterations would make every property pass without being checked.
+        if property_iterations == Some(0) {
+            return Err(ConfigError::new(format!(
+                "command line argument {} needs to be greater than 0",
+                Self::CMD_USER_PROP_ITERATIONS
+            )));
+        }
+
+        let only_scene_path = Self::get_flag(&mut args_vec, Self::CMD_USER_ONLY_SCENE_PATH)?;
+
+        let quiet_run = Self::get_flag(&mut args_vec, Self::CMD_USER_QUIET_RUN)?;
 
         let unrecognized_args = args_vec
             .iter()
@@ -134,6 +319,22 @@ impl CliConfig {
             only_scene_path,
             keyword,
             filters,
+            tags,
+            exclude_tags,
+            match_all_tags,
+            retries,
+            shuffle,
+            shuffle_seed,
+            shard,
+            only_case,
+            only_file,
+            files,
+            list_cases,
+            scenes,
+            isolate,
+            update_snapshots,
+            property_seed,
+            property_iterations,
             quiet_run,
         })
     }
@@ -163,35 +364,74 @@ impl CliConfig {
         }
     }
 
-    fn get_arg(args: &mut Vec<&GString>, get_arg: impl Into<GString>) -> bool {
-        let mut gotten = false;
-        let get_arg: GString = get_arg.into();
-        for (i, arg) in args.iter_mut().enumerate() {
-            let cur_arg = arg.clone();
-            if cur_arg == get_arg {
-                gotten = true;
-                args.remove(i);
-                break;
-            }
+    // Flags can be given a value, so the ones set by the sources of lower precedence can be turned off, eg. `--shuffle=false`.
+    fn get_flag(args: &mut Vec<&GString>, flag: &str) -> Result<Option<bool>, ConfigError> {
+        let Some(i) = args.iter().position(|arg| {
+            let arg = arg.to_string();
+            arg == flag || arg.starts_with(&format!("{flag}="))
+        }) else {
+            return Ok(None);
+        };
+        let arg = args.remove(i).to_string();
+        let Some((_, value)) = arg.split_once('=') else {
+            return Ok(Some(true));
+        };
+        match value.trim().to_lowercase().as_str() {
+            "true" | "1" => Ok(Some(true)),
+            "false" | "0" => Ok(Some(false)),
+            _ => Err(ConfigError::new(format!(
+                "command line argument {flag} needs to be `true` or `false`, got: `{value}`"
+            ))),
         }
-        gotten
-    }
-
-    fn get_arg_with_value(args: &mut Vec<&GString>, get_arg: &str) -> Vec<String> {
-        for (i, arg) in args.iter_mut().enumerate() {
-            let cur_arg = arg.clone();
-            let arg_str = cur_arg.to_string();
-            if arg_str.starts_with(get_arg) {
-                let values = arg_str.split('=').collect::<Vec<_>>()[1];
-                let values = values
-                    .split(',')
-                    .map(|str| str.to_owned())
-                    .collect::<Vec<String>>();
-                args.remove(i);
-                return values;
-            }
+    }
+
+    fn get_arg_with_value(
+        args: &mut Vec<&GString>,
+        get_arg: &str,
+    ) -> Result<Option<String>, ConfigError> {
+        let Some(i) = args.iter().position(|arg| {
+            let arg = arg.to_string();
+            arg == get_arg || arg.starts_with(&format!("{get_arg}="))
+        }) else {
+            return Ok(None);
+        };
+        let arg = args.remove(i).to_string();
+        match arg.split_once('=') {
+            Some((_, value)) => Ok(Some(value.to_owned())),
+            None => Err(ConfigError::new(format!(
+                "command line argument {get_arg} requires a value"
+            ))),
+        }
+    }
+
+    // Empty items are dropped, so empty value sets empty list, eg. `--tags=` clears the tags set by the sources of lower precedence.
+    fn get_arg_with_list(
+        args: &mut Vec<&GString>,
+        get_arg: &str,
+    ) -> Result<Option<Vec<String>>, ConfigError> {
+        Ok(Self::get_arg_with_value(args, get_arg)?.map(|values| {
+            values
+                .split(',')
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_owned())
+                .collect()
+        }))
+    }
+
+    fn get_arg_with_number<T: std::str::FromStr>(
+        args: &mut Vec<&GString>,
+        get_arg: &str,
+    ) -> Result<Option<T>, ConfigError> {
+        let Some(value) = Self::get_arg_with_value(args, get_arg)? else {
+            return Ok(None);
+        };
+        match value.parse::<T>() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(ConfigError::new(format!(
+                "command line argument {} needs to be a non-negative integer",
+                get_arg
+            ))),
         }
-        Vec::new()
     }
 }
 
@@ -206,7 +446,24 @@ pub(crate) struct RunnerConfig {
     only_scene_path: bool,
     scene_path: String,
     filters: Vec<String>,
+    tags: Vec<String>,
+    exclude_tags: Vec<String>,
+    match_all_tags: bool,
+    retries: u32,
+    shuffle: bool,
+    shuffle_seed: Option<u64>,
+    shard: Option<Shard>,
+    only_case: Option<String>,
+    only_file: Option<String>,
+    files: Vec<String>,
+    list_cases: bool,
+    scenes: Vec<String>,
+    isolate: bool,
+    update_snapshots: bool,
+    property_seed: Option<u64>,
+    property_iterations: Option<u32>,
     quiet_run: bool,
+    sources: Vec<&'static str>,
 }
 
 impl RunnerConfig {
@@ -230,6 +487,76 @@ impl RunnerConfig {
         &self.filters
     }
 
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn exclude_tags(&self) -> &Vec<String> {
+        &self.exclude_tags
+    }
+
+    pub fn match_all_tags(&self) -> bool {
+        self.match_all_tags
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Seed used to shuffle cases order. If `None`, cases are run in deterministic order.
+    pub fn shuffle_seed(&self) -> Option<u64> {
+        self.shuffle_seed
+    }
+
+    pub fn shard(&self) -> Option<Shard> {
+        self.shard
+    }
+
+    /// Qualified name of the only case to run, used to run every case in separate process.
+    pub fn only_case(&self) -> Option<&str> {
+        self.only_case.as_deref()
+    }
+
+    /// File of the only cases to run, used to run every file in separate process.
+    pub fn only_file(&self) -> Option<&str> {
+        self.only_file.as_deref()
+    }
+
+    /// Paths of files with the cases to run. Matched against the ends of case files, so they can be relative to any ancestor directory.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// If `true`, selected cases are listed instead of being run.
+    pub fn list_cases(&self) -> bool {
+        self.list_cases
+    }
+
+    /// Paths of the scenes to run one after another, in one invocation.
+    pub fn scenes(&self) -> &[String] {
+        &self.scenes
+    }
+
+    /// If `true`, every case is run with its own temporary root node.
+    pub fn isolate(&self) -> bool {
+        self.isolate
+    }
+
+    /// If `true`, snapshot assertions write the current values instead of comparing them.
+    pub fn update_snapshots(&self) -> bool {
+        self.update_snapshots
+    }
+
+    /// Seed used by property-based tests. If `None`, new seed is generated for every property.
+    pub fn property_seed(&self) -> Option<u64> {
+        self.property_seed
+    }
+
+    /// Number of inputs checked by property-based tests. If `None`, the default is used.
+    pub fn property_iterations(&self) -> Option<u32> {
+        self.property_iterations
+    }
+
     pub fn run_rust_tests(&self) -> bool {
         self.run_rust_tests
     }
@@ -250,6 +577,11 @@ impl RunnerConfig {
         self.quiet_run
     }
 
+    /// Sources of the options applied to this run, in order of increasing precedence.
+    pub fn sources(&self) -> &[&'static str] {
+        &self.sources
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         disallow_focus: bool,
@@ -261,84 +593,273 @@ impl RunnerConfig {
         only_scene_path: bool,
         scene_path: String,
         filters: &PackedStringArray,
+        tags: &PackedStringArray,
+        exclude_tags: &PackedStringArray,
+        match_all_tags: bool,
+        retries: u32,
+        shuffle: bool,
+        isolate: bool,
         quiet_run: bool,
     ) -> Result<Self, ConfigError> {
-        let keyword = keyword.to_string();
-        let filters = filters
-            .as_slice()
-            .iter()
-            .map(|str| str.to_string())
-            .collect::<Vec<_>>();
-
         let mut instance = Self {
-            disallow_focus,
-            disallow_skip,
-            run_rust_tests,
-            run_rust_benchmarks,
-            ignore_keywords,
-            keyword,
-            only_scene_path,
+            run_rust_tests: true,
+            run_rust_benchmarks: true,
             scene_path,
-            filters,
-            quiet_run,
+            ..Default::default()
         };
 
-        if !is_headless_run() {
-            return Ok(instance);
+        if let Some(file) = CliConfig::from_file()? {
+            instance.apply(file);
+            instance.sources.push(CONFIG_FILE_PATH);
         }
 
-        let cmdline = CliConfig::from_os()?;
+        // Node properties are treated as set only if they differ from their default values.
+        instance.apply(CliConfig {
+            disallow_focus: disallow_focus.then_some(true),
+            disallow_skip: disallow_skip.then_some(true),
+            ignore_keywords: ignore_keywords.then_some(true),
+            only_scene_path: only_scene_path.then_some(true),
+            keyword: keyword.to_string(),
+            filters: Self::to_non_empty_vec(filters),
+            tags: Self::to_non_empty_vec(tags),
+            exclude_tags: Self::to_non_empty_vec(exclude_tags),
+            match_all_tags: match_all_tags.then_some(true),
+            retries: Some(retries).filter(|retries| *retries > 0),
+            shuffle: shuffle.then_some(true),
+            isolate: isolate.then_some(true),
+            quiet_run: quiet_run.then_some(true),
+            ..Default::default()
+        });
+        if !(run_rust_tests && run_rust_benchmarks) {
+            instance.run_rust_tests = run_rust_tests;
+            instance.run_rust_benchmarks = run_rust_benchmarks;
+        }
+        instance.sources.push("node properties");
 
-        if cmdline.run_rust_tests || cmdline.run_rust_benchmarks {
-            instance.run_rust_tests = cmdline.run_rust_tests;
-            instance.run_rust_benchmarks = cmdline.run_rust_benchmarks;
+        if let Some(env) = CliConfig::from_env()? {
+            instance.apply(env);
+            instance.sources.push("environment");
         }
-        if cmdline.allow_focus {
-            instance.disallow_focus = false
-        };
-        if cmdline.disallow_focus {
-            instance.disallow_focus = true
+
+        if is_headless_run() {
+            instance.apply(CliConfig::from_os()?);
+            instance.sources.push("command line");
+        }
+
+        instance.check_scenes()?;
+        instance.resolve_shuffle_seed();
+        Ok(instance)
+    }
+
+    // Options which are set override the values from the sources of lower precedence.
+    fn apply(&mut self, options: CliConfig) {
+        // Selecting one kind of cases runs only the selected kinds, while turning one off leaves the other unchanged.
+        if options.run_rust_tests == Some(true) || options.run_rust_benchmarks == Some(true) {
+            self.run_rust_tests = options.run_rust_tests == Some(true);
+            self.run_rust_benchmarks = options.run_rust_benchmarks == Some(true);
+        } else {
+            if options.run_rust_tests == Some(false) {
+                self.run_rust_tests = false;
+            }
+            if options.run_rust_benchmarks == Some(false) {
+                self.run_rust_benchmarks = false;
+            }
+        }
+        if let Some(allow_focus) = options.allow_focus {
+            self.disallow_focus = !allow_focus
         };
-        if cmdline.allow_skip {
-            instance.disallow_skip = false
+        if let Some(disallow_focus) = options.disallow_focus {
+            self.disallow_focus = disallow_focus
         };
-        if cmdline.disallow_skip {
-            instance.disallow_skip = true
+        if let Some(allow_skip) = options.allow_skip {
+            self.disallow_skip = !allow_skip
         };
-        if cmdline.mute_filters {
-            instance.filters = Vec::new()
+        if let Some(disallow_skip) = options.disallow_skip {
+            self.disallow_skip = disallow_skip
         };
-        if !cmdline.filters.is_empty() {
-            instance.filters.clone_from(&cmdline.filters)
+        if options.mute_filters == Some(true) {
+            self.filters = Vec::new()
         };
-        if cmdline.mute_keyword {
-            instance.keyword = String::new()
+        if let Some(filters) = options.filters {
+            self.filters = filters;
+        }
+        if options.mute_keyword == Some(true) {
+            self.keyword = String::new()
         };
-        if cmdline.ignore_keywords {
-            instance.ignore_keywords = true;
+        if let Some(ignore_keywords) = options.ignore_keywords {
+            self.ignore_keywords = ignore_keywords;
         }
-        if !cmdline.keyword.is_empty() {
-            instance.keyword.clone_from(&cmdline.keyword)
+        if !options.keyword.is_empty() {
+            self.keyword.clone_from(&options.keyword)
         };
-        if cmdline.only_scene_path {
-            instance.only_scene_path = true;
+        if let Some(tags) = options.tags {
+            self.tags = tags;
+        }
+        if let Some(exclude_tags) = options.exclude_tags {
+            self.exclude_tags = exclude_tags;
+        }
+        if let Some(match_all_tags) = options.match_all_tags {
+            self.match_all_tags = match_all_tags;
+        }
+        if let Some(retries) = options.retries {
+            self.retries = retries;
+        }
+        if let Some(shuffle) = options.shuffle {
+            self.shuffle = shuffle;
+        }
+        if options.shuffle_seed.is_some() {
+            self.shuffle_seed = options.shuffle_seed;
+        }
+        if options.shard.is_some() {
+            self.shard = options.shard;
+        }
+        if options.only_case.is_some() {
+            self.only_case = options.only_case;
+        }
+        if options.only_file.is_some() {
+            self.only_file = options.only_file;
+        }
+        if let Some(files) = options.files {
+            self.files = files;
+        }
+        if let Some(list_cases) = options.list_cases {
+            self.list_cases = list_cases;
+        }
+        if let Some(scenes) = options.scenes {
+            self.scenes = scenes;
+        }
+        if let Some(isolate) = options.isolate {
+            self.isolate = isolate;
+        }
+        if let Some(update_snapshots) = options.update_snapshots {
+            self.update_snapshots = update_snapshots;
         }
-        if cmdline.quiet_run {
-            instance.quiet_run = true
+        if options.property_seed.is_some() {
+            self.property_seed = options.property_seed;
         }
+        if options.property_iterations.is_some() {
+            self.property_iterations = options.property_iterations;
+        }
+        if let Some(only_scene_path) = options.only_scene_path {
+            self.only_scene_path = only_scene_path;
+        }
+        if let Some(quiet_run) = options.quiet_run {
+            self.quiet_run = quiet_run
+        }
+    }
 
-        Ok(instance)
+    // Cases are listed and selected in one scene, so selecting them in the sequence of scenes would run the others without them.
+    fn check_scenes(&self) -> Result<(), ConfigError> {
+        if self.scenes.is_empty() {
+            return Ok(());
+        }
+        let conflicting = [
+            (self.list_cases, CliConfig::CMD_USER_LIST_CASES),
+            (self.only_case.is_some(), CliConfig::CMD_USER_ONLY_CASE),
+            (self.only_file.is_some(), CliConfig::CMD_USER_ONLY_FILE),
+        ];
+        for (is_set, arg) in conflicting {
+            CliConfig::check_mutually_exclusive_args(
+                true,
+                is_set,
+                CliConfig::CMD_USER_SCENES,
+                arg,
+            )?;
+        }
+        Ok(())
+    }
+
+    // Providing the seed implies shuffling. Otherwise new seed is generated, so it can be printed for the reproduction of the run.
+    fn resolve_shuffle_seed(&mut self) {
+        self.shuffle_seed = match (self.shuffle, self.shuffle_seed) {
+            (_, Some(seed)) => Some(seed),
+            (true, None) => Some(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|duration| duration.as_nanos() as u64)
+                    .unwrap_or_default(),
+            ),
+            (false, None) => None,
+        };
+    }
+
+    fn to_non_empty_vec(array: &PackedStringArray) -> Option<Vec<String>> {
+        Some(Self::to_string_vec(array)).filter(|values| !values.is_empty())
+    }
+
+    fn to_string_vec(array: &PackedStringArray) -> Vec<String> {
+        array
+            .as_slice()
+            .iter()
+            .map(|str| str.to_string())
+            .collect::<Vec<_>>()
+    }
+}
+
+/// Part of the cases to run, when they are split across multiple runs.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Shard {
+    /// 1-based index of the shard.
+    pub index: u64,
+    pub count: u64,
+}
+
+impl Shard {
+    fn parse(value: &str) -> Result<Self, ConfigError> {
+        let error = || {
+            ConfigError::new(format!(
+                "command line argument {} needs to be in `index/count` format, with index between 1 and count, got: `{value}`",
+                CliConfig::CMD_USER_SHARD
+            ))
+        };
+        let (index, count) = value.split_once('/').ok_or_else(error)?;
+        let index = index.trim().parse::<u64>().map_err(|_| error())?;
+        let count = count.trim().parse::<u64>().map_err(|_| error())?;
+        if index == 0 || index > count {
+            return Err(error());
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
     }
 }
 
-pub(crate) struct RunnerInfo {
-    pub mode: &'static str,
-    pub rust_build: &'static str,
-    pub godot_build: &'static str,
-    pub additional_message: Vec<String>,
+/// Information about the environment of the current run.
+///
+/// Passed to custom `skip_if` functions of `#[gditest]` and `#[gdbench]`.
+pub struct RunnerInfo {
+    pub(crate) mode: &'static str,
+    pub(crate) rust_build: &'static str,
+    pub(crate) godot_build: &'static str,
+    pub(crate) update_snapshots: bool,
+    pub(crate) additional_message: Vec<String>,
 }
 
 impl RunnerInfo {
+    /// Whether the runner was started in headless mode, eg. from the command line.
+    pub fn is_headless(&self) -> bool {
+        self.mode == "HEADLESS"
+    }
+
+    /// Whether the Rust code was built in debug mode.
+    pub fn is_rust_debug(&self) -> bool {
+        self.rust_build == "debug"
+    }
+
+    /// Whether the Godot executable is a debug build.
+    pub fn is_godot_debug(&self) -> bool {
+        self.godot_build == "debug"
+    }
+
+    /// Whether snapshot assertions write the current values instead of comparing them, as with `--update-snapshots` argument.
+    pub fn is_updating_snapshots(&self) -> bool {
+        self.update_snapshots
+    }
+
     pub(crate) fn gather(config: &RunnerConfig) -> Self {
         let mode = if is_headless_run() {
             "HEADLESS"
@@ -363,6 +884,50 @@ impl RunnerInfo {
         if config.ignore_keywords() {
             additional_message.push("ignoring keywords".to_owned());
         }
+        if !config.tags().is_empty() {
+            let matching = if config.match_all_tags() {
+                "all"
+            } else {
+                "any"
+            };
+            additional_message.push(format!(
+                "using {matching} of TAGS: '{}'",
+                config.tags().join("', '")
+            ));
+        }
+        if !config.exclude_tags().is_empty() {
+            additional_message.push(format!(
+                "excluding TAGS: '{}'",
+                config.exclude_tags().join("', '")
+            ));
+        }
+        if config.retries() > 0 {
+            additional_message.push(format!("retrying failed {} times", config.retries()));
+        }
+        if let Some(seed) = config.shuffle_seed() {
+            additional_message.push(format!("shuffled with SEED: {seed}"));
+        }
+        if let Some(shard) = config.shard() {
+            additional_message.push(format!("running SHARD: {shard}"));
+        }
+        if let Some(case) = config.only_case() {
+            additional_message.push(format!("only CASE: {case}"));
+        }
+        if let Some(file) = config.only_file() {
+            additional_message.push(format!("only FILE: {file}"));
+        }
+        if !config.files().is_empty() {
+            additional_message.push(format!("in FILES: {}", config.files().join(", ")));
+        }
+        if config.isolate() {
+            additional_message.push("isolating cases".to_owned());
+        }
+        if config.update_snapshots() {
+            additional_message.push("updating snapshots".to_owned());
+        }
+        if let Some(seed) = config.property_seed() {
+            additional_message.push(format!("property SEED: {seed}"));
+        }
         if config.only_scene_path() {
             additional_message.push("scene path specific".to_owned())
         }
@@ -371,6 +936,7 @@ impl RunnerInfo {
             mode,
             rust_build,
             godot_build,
+            update_snapshots: config.update_snapshots(),
             additional_message,
         }
     }
@@ -0,0 +1,86 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Display;
+
+use godot::classes::Node;
+use godot::obj::{Gd, InstanceId, NewAlloc};
+
+/// Temporary root node for a single case, used when the runner isolates cases.
+pub(crate) struct CaseIsolation {
+    runner: Gd<Node>,
+    root: Gd<Node>,
+    runner_children: HashSet<InstanceId>,
+}
+
+impl CaseIsolation {
+    const ROOT_NAME: &'static str = "CaseRoot";
+
+    pub(crate) fn new(runner: Gd<Node>) -> Self {
+        let runner_children = runner
+            .get_children()
+            .iter_shared()
+            .map(|child| child.instance_id())
+            .collect();
+
+        let mut root = Node::new_alloc();
+        root.set_name(Self::ROOT_NAME);
+        runner.clone().add_child(&root);
+
+        Self {
+            runner,
+            root,
+            runner_children,
+        }
+    }
+
+    pub(crate) fn root(&self) -> Gd<Node> {
+        self.root.clone()
+    }
+
+    /// Frees the case root along with its children. Nodes added to the runner during the case are also freed, and reported as an error.
+    pub(crate) fn finish(mut self) -> Result<(), StrayNodesError> {
+        Self::detach_and_free(&mut self.runner, self.root);
+
+        let mut stray_nodes = Vec::new();
+        for child in self.runner.get_children().iter_shared() {
+            if !self.runner_children.contains(&child.instance_id()) {
+                stray_nodes.push(child.get_name().to_string());
+                Self::detach_and_free(&mut self.runner, child);
+            }
+        }
+
+        if stray_nodes.is_empty() {
+            return Ok(());
+        }
+        Err(StrayNodesError { stray_nodes })
+    }
+
+    // Detach right away, so the node won't collide with the next case nodes before being freed.
+    fn detach_and_free(runner: &mut Gd<Node>, mut node: Gd<Node>) {
+        runner.remove_child(&node);
+        node.queue_free();
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct StrayNodesError {
+    stray_nodes: Vec<String>,
+}
+
+impl Display for StrayNodesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "nodes added outside of the case root: `{}`",
+            self.stray_nodes.join("`, `")
+        )
+    }
+}
+
+impl Error for StrayNodesError {}
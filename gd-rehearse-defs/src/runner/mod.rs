@@ -8,8 +8,11 @@ use godot::builtin::GString;
 
 pub(crate) mod class;
 pub mod config;
+pub(crate) mod isolation;
 pub(crate) mod panic;
 pub(crate) mod print;
+pub(crate) mod scenes;
+pub(crate) mod tests_run;
 
 pub use class::GdTestRunner;
 
@@ -37,7 +37,7 @@ fn unpack_err(err: Box<dyn Any + Send>) -> UnwindError {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct UnwindError {
     message: String,
 }
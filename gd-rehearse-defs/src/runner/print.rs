@@ -77,6 +77,7 @@ impl MessageWriter {
             rust_build,
             godot_build,
             additional_message,
+            ..
         } = RunnerInfo::gather(config);
 
         self.println(&format!(
@@ -92,6 +93,11 @@ impl MessageWriter {
             format!("Rust build: {rust_build}; Godot build: {godot_build}")
         ));
 
+        self.println(&format!(
+            "{:^80}",
+            format!("Options from: {}", config.sources().join(" < "))
+        ));
+
         if !additional_message.is_empty() {
             self.println(&format!("{:^80}", additional_message.join(" & ")));
         }
@@ -106,11 +112,11 @@ impl MessageWriter {
         self.println("");
     }
 
-    pub fn print_test_pre(&self, test: impl Case, last_file: &mut Option<String>) {
+    pub fn print_test_pre(&self, test: impl Case, last_module: &mut Option<String>) {
         if self.quiet {
             return;
         }
-        self.print_file_header(test.get_case_file(), last_file);
+        self.print_module_header(&test, last_module);
         // If printing to Godot console, the result will be printed as a whole string. That's because every `godot_print!` prints
         // the whole line only and there is no alternative for appending to the godot console output.
         if self.to_godot {
@@ -119,24 +125,31 @@ impl MessageWriter {
         print!("   -- {} ... ", test.get_case_name());
     }
 
-    fn print_file_header(&self, file: &str, last_file: &mut Option<String>) {
-        // Check if we need to open a new category for a file.
-        let is_new_file = last_file
+    fn print_module_header(&self, case: &impl Case, last_module: &mut Option<String>) {
+        let module = case.get_case_module_path();
+
+        // Check if we need to open a new category for a module.
+        let is_new_module = last_module
             .as_ref()
-            .is_none_or(|last_file| last_file != file);
+            .is_none_or(|last_module| last_module != module);
 
-        if !is_new_file {
+        if !is_new_module {
             return;
         }
 
+        let header = format!(
+            "{module} ({file}):",
+            file = extract_file_subtitle(case.get_case_file())
+        );
+
         // For the first case to omit `\n`.
-        if last_file.is_none() {
-            self.println(&format!("   {}:", extract_file_subtitle(file)));
+        if last_module.is_none() {
+            self.println(&format!("   {header}"));
         } else {
-            self.println(&format!("\n   {}:", extract_file_subtitle(file)));
+            self.println(&format!("\n   {header}"));
         }
-        // State update for file-category-print
-        *last_file = Some(file.to_owned());
+        // State update for module-category-print
+        *last_module = Some(module.to_owned());
     }
 
     pub fn print_test_post(&self, test_case: &str, result: TestResult) {
@@ -145,6 +158,8 @@ impl MessageWriter {
         }
         let outcome = if let Some(err) = result.error {
             format!("{outcome}:\n{err}", outcome = result.outcome)
+        } else if let Some(message) = result.message {
+            format!("{outcome} ({message})", outcome = result.outcome)
         } else {
             format!("{outcome}", outcome = result.outcome)
         };
@@ -158,11 +173,11 @@ impl MessageWriter {
         }
     }
 
-    pub fn print_bench_pre(&self, benchmark: &impl Case, last_file: &mut Option<String>) {
+    pub fn print_bench_pre(&self, benchmark: &impl Case, last_module: &mut Option<String>) {
         if self.quiet {
             return;
         }
-        self.print_file_header(benchmark.get_case_file(), last_file);
+        self.print_module_header(benchmark, last_module);
 
         // If printing to Godot console, the result will be printed as a whole string. That's because every `godot_print!` prints
         // the whole line only and there is no alternative for appending to the godot console output.
@@ -203,7 +218,10 @@ impl MessageWriter {
                     err = result.error.expect("couldn't unwrap error")
                 )
             }
-            _ => format!("    {}", result.outcome),
+            _ => match result.message {
+                Some(message) => format!("    {} ({message})", result.outcome),
+                None => format!("    {}", result.outcome),
+            },
         };
 
         if self.to_godot {
@@ -0,0 +1,158 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Running several scenes in one invocation with `--scenes` argument.
+//!
+//! Every scene needs to have `GdTestRunner` as its root. Runner of the launched scene only begins the sequence, changing into the first
+//! scene. Runner of every scene executes its cases, records the outcome and changes into the next one. Runner of the last scene prints
+//! the combined summary and quits with the combined exit code.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use godot::builtin::{GString, StringName};
+use godot::classes::{Node, PackedScene};
+use godot::global::Error;
+use godot::obj::Gd;
+use godot::tools::try_load;
+
+use crate::cases::CaseOutcome;
+
+use super::print::MessageWriter;
+
+struct SceneSequence {
+    remaining: VecDeque<String>,
+    outcomes: Vec<(String, bool)>,
+    failed_cases: Vec<String>,
+}
+
+thread_local! {
+    // Runners are freed when the scene changes, so the state of the sequence is kept outside of them.
+    static SEQUENCE: RefCell<Option<SceneSequence>> = const { RefCell::new(None) };
+}
+
+/// Returns `true` if the current scene is run as a part of the sequence.
+pub(crate) fn in_sequence() -> bool {
+    SEQUENCE.with_borrow(|sequence| sequence.is_some())
+}
+
+/// Begins the sequence and changes into its first scene. Returns the combined outcome if none of the scenes could be run.
+pub(crate) fn begin(runner: &Gd<Node>, scenes: &[String]) -> Option<bool> {
+    MessageWriter::new(false).println(&format!("   Running scenes: {}", scenes.join(", ")));
+    SEQUENCE.set(Some(SceneSequence {
+        remaining: scenes.iter().cloned().collect(),
+        outcomes: Vec::new(),
+        failed_cases: Vec::new(),
+    }));
+    if change_to_next(runner) {
+        return None;
+    }
+    SEQUENCE.take().map(print_summary)
+}
+
+/// Adds the reasons of the failure of the current scene, like its failed cases, to the combined summary.
+pub(crate) fn record_failures(scene: &str, reasons: &[String]) {
+    SEQUENCE.with_borrow_mut(|sequence| {
+        if let Some(sequence) = sequence {
+            sequence
+                .failed_cases
+                .extend(reasons.iter().map(|reason| format!("{reason} in {scene}")));
+        }
+    });
+}
+
+/// Records outcome of the current scene and changes into the next one. Returns the combined outcome after the last scene, after printing
+/// the combined summary.
+pub(crate) fn finish_scene(runner: &Gd<Node>, scene: &str, outcome: bool) -> Option<bool> {
+    SEQUENCE.with_borrow_mut(|sequence| {
+        if let Some(sequence) = sequence {
+            sequence.outcomes.push((scene.to_owned(), outcome));
+        }
+    });
+    if change_to_next(runner) {
+        return None;
+    }
+    SEQUENCE.take().map(print_summary)
+}
+
+// Scenes which cannot be loaded or don't have `GdTestRunner` root are recorded as failed and skipped. Returns `false` if no scenes are
+// left.
+fn change_to_next(runner: &Gd<Node>) -> bool {
+    loop {
+        let Some(scene) =
+            SEQUENCE.with_borrow_mut(|sequence| sequence.as_mut()?.remaining.pop_front())
+        else {
+            return false;
+        };
+
+        match load_runner_scene(&scene) {
+            Ok(packed) => {
+                let mut tree = runner.get_tree().expect("runner should be inside tree");
+                if tree.change_scene_to_packed(&packed) == Error::OK {
+                    return true;
+                }
+                record_invalid_scene(&scene, "cannot change into the scene");
+            }
+            Err(reason) => record_invalid_scene(&scene, &reason),
+        }
+    }
+}
+
+fn load_runner_scene(scene: &str) -> Result<Gd<PackedScene>, String> {
+    let packed = try_load::<PackedScene>(&GString::from(scene))
+        .map_err(|err| format!("cannot load the scene: {err}"))?;
+    let root_type = packed
+        .get_state()
+        .filter(|state| state.get_node_count() > 0)
+        .map(|state| state.get_node_type(0));
+    if root_type != Some(StringName::from("GdTestRunner")) {
+        return Err("root of the scene isn't `GdTestRunner`".to_owned());
+    }
+    Ok(packed)
+}
+
+fn record_invalid_scene(scene: &str, reason: &str) {
+    MessageWriter::new(false).println(&format!("   Skipping scene {scene}: {reason}"));
+    SEQUENCE.with_borrow_mut(|sequence| {
+        if let Some(sequence) = sequence {
+            sequence.outcomes.push((scene.to_owned(), false));
+            sequence.failed_cases.push(format!("{scene}: {reason}"));
+        }
+    });
+}
+
+fn print_summary(sequence: SceneSequence) -> bool {
+    let writer = MessageWriter::new(false);
+    writer.println("");
+    writer.print_horizontal_separator();
+    writer.println("   Scenes summary");
+    writer.print_horizontal_separator();
+    writer.println("");
+
+    for (scene, outcome) in sequence.outcomes.iter() {
+        writer.println(&format!(
+            "   -- {scene} ... {}",
+            CaseOutcome::from_bool(*outcome)
+        ));
+    }
+
+    let passed = sequence.outcomes.iter().filter(|(_, ok)| *ok).count();
+    let failed = sequence.outcomes.len() - passed;
+    let all_passed = failed == 0;
+    writer.println(&format!(
+        "\nScenes result: {} {passed} passed; {failed} failed.",
+        CaseOutcome::from_bool(all_passed)
+    ));
+
+    if !sequence.failed_cases.is_empty() {
+        writer.println("\n  Failed:");
+        for case in sequence.failed_cases.iter() {
+            writer.println(&format!("  * {case}"));
+        }
+    }
+
+    all_passed
+}
@@ -0,0 +1,48 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::Instant;
+
+use crate::cases::rust_test_case::{PendingTicks, RustTestCase, TestContext};
+use crate::registry::itest::{GdRustItests, TestResult};
+
+use super::config::RunnerInfo;
+use super::isolation::CaseIsolation;
+
+/// State of Rust tests run, kept between physics frames while some test waits for physics ticks.
+pub(crate) struct RustTestsRun {
+    pub(crate) handler: GdRustItests,
+    pub(crate) info: RunnerInfo,
+    pub(crate) clock: Instant,
+    pub(crate) last_module: Option<String>,
+    pub(crate) current: Option<Box<RunningTest>>,
+}
+
+impl RustTestsRun {
+    pub(crate) fn new(handler: GdRustItests, info: RunnerInfo) -> Self {
+        Self {
+            handler,
+            info,
+            clock: Instant::now(),
+            last_module: None,
+            current: None,
+        }
+    }
+}
+
+/// Single attempt of a test, which can be suspended to wait for physics ticks.
+pub(crate) struct RunningTest {
+    pub(crate) test: RustTestCase,
+    pub(crate) ctx: TestContext,
+    pub(crate) isolation: Option<CaseIsolation>,
+    pub(crate) attempts: u32,
+    pub(crate) pending: Option<PendingTicks>,
+}
+
+pub(crate) enum TestProgress {
+    Finished(TestResult),
+    Suspended(Box<RunningTest>),
+}
@@ -0,0 +1,242 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Snapshot testing of node trees and resources.
+//!
+//! Snapshots are stored in `snapshots` directory next to the file declaring the test. Missing or changed snapshots fail the test, until
+//! the runner is started with `--update-snapshots` argument, which writes the current values instead.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use godot::builtin::{Dictionary, StringName};
+use godot::classes::{Node, Resource};
+use godot::global::{var_to_str, PropertyUsageFlags};
+use godot::obj::{EngineBitfield, Gd, Inherits};
+
+static UPDATE_SNAPSHOTS: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_update_snapshots(update: bool) {
+    UPDATE_SNAPSHOTS.store(update, Ordering::Relaxed);
+}
+
+/// Asserts that the content is the same as the snapshot stored under `name`.
+///
+/// Content can be any string - use [`snapshot_node_tree`](crate::snapshot::snapshot_node_tree) and
+/// [`snapshot_resource`](crate::snapshot::snapshot_resource) to serialize Godot objects to a stable text form. On mismatch, the test
+/// fails with a line diff.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_rehearse::itest::*;
+///
+/// #[gditest(scene = "res://level.tscn")]
+/// fn level_layout(ctx: &TestContext) {
+///     let level = ctx.scene();
+///     assert_snapshot!("level_layout", snapshot_node_tree(&level, &["position"]));
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $content:expr $(,)?) => {
+        $crate::snapshot::assert_snapshot_impl(
+            $name,
+            ::std::convert::AsRef::<str>::as_ref(&$content),
+            ::std::file!(),
+            ::std::env!("CARGO_MANIFEST_DIR"),
+        )
+    };
+}
+
+/// Serializes the node and its descendants to a stable text form, with one line for every node name and class. Values of `properties`
+/// are listed below every node which has them.
+pub fn snapshot_node_tree<T: Inherits<Node>>(node: &Gd<T>, properties: &[&str]) -> String {
+    let mut out = String::new();
+    write_node(&node.clone().upcast(), properties, 0, &mut out);
+    out
+}
+
+fn write_node(node: &Gd<Node>, properties: &[&str], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    out.push_str(&format!(
+        "{indent}{} ({})\n",
+        node.get_name(),
+        node.get_class()
+    ));
+
+    let node_properties = property_names(node.get_property_list());
+    for property in properties {
+        let property = StringName::from(*property);
+        if node_properties.contains(&property) {
+            let value = var_to_str(&node.get(&property));
+            out.push_str(&format!("{indent}  {property} = {value}\n"));
+        }
+    }
+
+    for child in node.get_children().iter_shared() {
+        write_node(&child, properties, depth + 1, out);
+    }
+}
+
+/// Serializes the resource class and its stored properties to a stable text form.
+pub fn snapshot_resource<T: Inherits<Resource>>(resource: &Gd<T>) -> String {
+    let resource = resource.clone().upcast::<Resource>();
+    let mut out = format!("{}\n", resource.get_class());
+
+    for info in resource.get_property_list().iter_shared() {
+        let usage = info.get("usage").map(|v| v.to::<u64>()).unwrap_or_default();
+        if usage & PropertyUsageFlags::STORAGE.ord() == 0 {
+            continue;
+        }
+        let Some(name) = info.get("name").map(|v| v.to::<StringName>()) else {
+            continue;
+        };
+        // Path depends on where the resource was loaded from, not on its content.
+        if name == StringName::from("resource_path") {
+            continue;
+        }
+        let value = var_to_str(&resource.get(&name));
+        out.push_str(&format!("  {name} = {value}\n"));
+    }
+    out
+}
+
+fn property_names(list: godot::builtin::Array<Dictionary>) -> Vec<StringName> {
+    list.iter_shared()
+        .filter_map(|info| info.get("name").map(|v| v.to::<StringName>()))
+        .collect()
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_snapshot_impl(name: &str, content: &str, file: &str, manifest_dir: &str) {
+    let path = snapshot_path(name, file, manifest_dir);
+
+    if UPDATE_SNAPSHOTS.load(Ordering::Relaxed) {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .unwrap_or_else(|err| panic!("cannot create `{}`: {err}", dir.display()));
+        }
+        std::fs::write(&path, content)
+            .unwrap_or_else(|err| panic!("cannot write snapshot `{}`: {err}", path.display()));
+        return;
+    }
+
+    let Ok(stored) = std::fs::read_to_string(&path) else {
+        panic!(
+            "snapshot `{name}` not found at `{}`. Run with `--update-snapshots` to create it",
+            path.display()
+        );
+    };
+
+    if stored != content {
+        panic!(
+            "snapshot `{name}` doesn't match `{}`. Run with `--update-snapshots` to accept the changes\n{}",
+            path.display(),
+            line_diff(&stored, content)
+        );
+    }
+}
+
+// `file!()` is relative to the workspace root, which can be one of the ancestors of the crate manifest directory.
+fn snapshot_path(name: &str, file: &str, manifest_dir: &str) -> PathBuf {
+    let file = Path::new(file);
+    let source = Path::new(manifest_dir)
+        .ancestors()
+        .map(|dir| dir.join(file))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| Path::new(manifest_dir).join(file));
+
+    let stem = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    source
+        .parent()
+        .unwrap_or(Path::new(manifest_dir))
+        .join("snapshots")
+        .join(format!("{stem}__{name}.snap"))
+}
+
+/// Line diff of the stored and the current content, with `-` marking lines only in the stored one and `+` only in the current one.
+fn line_diff(stored: &str, current: &str) -> String {
+    let old = stored.lines().collect::<Vec<_>>();
+    let new = current.lines().collect::<Vec<_>>();
+
+    // Longest common subsequence lengths, from the end.
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::from("  --- stored\n  +++ current");
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!("\n    {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("\n  - {}", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("\n  + {}", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::line_diff;
+
+    #[test]
+    fn line_diff_keeps_unchanged_lines() {
+        assert_eq!(
+            line_diff(
+                "Root (Node)\n  Child (Node)\n",
+                "Root (Node)\n  Child (Node)\n"
+            ),
+            "  --- stored\n  +++ current\n    Root (Node)\n      Child (Node)"
+        );
+    }
+
+    #[test]
+    fn line_diff_marks_changed_lines() {
+        assert_eq!(
+            line_diff(
+                "Root (Node)\n  position = Vector2(1, 2)\n  Child (Node)\n",
+                "Root (Node)\n  position = Vector2(3, 4)\n  Child (Node)\n"
+            ),
+            "  --- stored\n  +++ current\n    Root (Node)\n  -   position = Vector2(1, 2)\n  +   position = Vector2(3, 4)\n      Child (Node)"
+        );
+    }
+
+    #[test]
+    fn line_diff_marks_added_and_removed_lines() {
+        assert_eq!(
+            line_diff("a\nb\nc\n", "a\nc\nd\n"),
+            "  --- stored\n  +++ current\n    a\n  - b\n    c\n  + d"
+        );
+        assert_eq!(
+            line_diff("", "a\nb"),
+            "  --- stored\n  +++ current\n  + a\n  + b"
+        );
+        assert_eq!(
+            line_diff("a\nb", ""),
+            "  --- stored\n  +++ current\n  - a\n  - b"
+        );
+    }
+}
@@ -35,9 +35,13 @@ pub fn attribute_bench(input_decl: Declaration) -> Result<TokenStream, venial::E
 
     let mut repeats = DEFAULT_REPETITIONS;
     let mut focused = false;
+    let mut skip_reason = quote! { None };
+    let mut skip_condition = quote! { None };
     let mut skipped = false;
     let mut keyword = quote! { None };
     let mut scene_path = quote! { None };
+    let mut scene = quote! { None };
+    let mut tags = Vec::new();
     let mut setup_function: Option<Ident> = None;
     let mut cleanup_function: Option<Ident> = None;
 
@@ -47,9 +51,12 @@ pub fn attribute_bench(input_decl: Declaration) -> Result<TokenStream, venial::E
     while let Some(ident) = parser.get_one_of_idents(&[
         AttributeIdent::Focus,
         AttributeIdent::Skip,
+        AttributeIdent::SkipIf,
         AttributeIdent::Repeat,
         AttributeIdent::Keyword,
+        AttributeIdent::Tags,
         AttributeIdent::ScenePath,
+        AttributeIdent::Scene,
         AttributeIdent::Setup,
         AttributeIdent::Cleanup,
     ])? {
@@ -69,7 +76,18 @@ pub fn attribute_bench(input_decl: Declaration) -> Result<TokenStream, venial::E
             }
             AttributeIdent::Skip => {
                 skipped = true;
-                parser.progress_puct()
+                if parser.is_next_equal_sign() {
+                    parser.pop_equal_sign()?;
+                    let reason_lit = parser.get_literal()?;
+                    skip_reason = quote! { Some( #reason_lit ) };
+                }
+                parser.progress_puct();
+            }
+            AttributeIdent::SkipIf => {
+                parser.pop_equal_sign()?;
+                let condition = parser.get_skip_condition(quote! { ::gd_rehearse::bench })?;
+                skip_condition = quote! { Some( #condition ) };
+                parser.progress_puct();
             }
             AttributeIdent::Keyword => {
                 parser.pop_equal_sign()?;
@@ -77,12 +95,23 @@ pub fn attribute_bench(input_decl: Declaration) -> Result<TokenStream, venial::E
                 keyword = quote! { Some( #keyword_lit ) };
                 parser.progress_puct();
             }
+            AttributeIdent::Tags => {
+                parser.pop_equal_sign()?;
+                tags = parser.get_literal_array()?;
+                parser.progress_puct();
+            }
             AttributeIdent::ScenePath => {
                 parser.pop_equal_sign()?;
-                let scene_path_lit = parser.get_literal_scene_path()?;
+                let scene_path_lit = parser.get_literal_scene_path(AttributeIdent::ScenePath)?;
                 scene_path = quote! { Some( #scene_path_lit ) };
                 parser.progress_puct();
             }
+            AttributeIdent::Scene => {
+                parser.pop_equal_sign()?;
+                let scene_lit = parser.get_literal_scene_path(AttributeIdent::Scene)?;
+                scene = quote! { Some( #scene_lit ) };
+                parser.progress_puct();
+            }
             AttributeIdent::Setup => {
                 parser.pop_equal_sign()?;
                 setup_function = Some(parser.get_ident()?);
@@ -93,6 +122,7 @@ pub fn attribute_bench(input_decl: Declaration) -> Result<TokenStream, venial::E
                 cleanup_function = Some(parser.get_ident()?);
                 parser.progress_puct();
             }
+            _ => unreachable!(),
         }
     }
 
@@ -162,12 +192,17 @@ pub fn attribute_bench(input_decl: Declaration) -> Result<TokenStream, venial::E
           name: #bench_name_str,
           focused: #focused,
           skipped: #skipped,
+          skip_reason: #skip_reason,
+          skip_condition: #skip_condition,
           keyword: #keyword,
+          tags: &[#(#tags),*],
           file: std::file!(),
+          module_path: std::module_path!(),
           line: std::line!(),
           function: #bench_name,
           repetitions: #repeats,
           scene_path: #scene_path,
+          scene: #scene,
           setup_function: #setup_function,
           cleanup_function: #cleanup_function
         }}
@@ -7,7 +7,7 @@
 use crate::parser::{AttributeIdent, AttributeValueParser};
 use crate::utils::bail;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
 use venial::{Declaration, Error, FnParam, Function};
 
@@ -28,8 +28,16 @@ pub fn attribute_gditest(input_decl: Declaration) -> Result<TokenStream, Error>
 
     let mut skipped = false;
     let mut focused = false;
+    let mut skip_reason = quote! { None };
+    let mut skip_condition = quote! { None };
     let mut keyword = quote! { None };
     let mut scene_path = quote! { None };
+    let mut scene = quote! { None };
+    let mut tags = Vec::new();
+    let mut xfail = quote! { None };
+    let mut retries = 0_u32;
+    let mut setup_function: Option<Ident> = None;
+    let mut teardown_function: Option<Ident> = None;
 
     let mut parser =
         AttributeValueParser::from_attribute_group_at_path(&func.attributes, "gditest")?;
@@ -37,8 +45,15 @@ pub fn attribute_gditest(input_decl: Declaration) -> Result<TokenStream, Error>
     while let Some(ident) = parser.get_one_of_idents(&[
         AttributeIdent::Focus,
         AttributeIdent::Skip,
+        AttributeIdent::SkipIf,
         AttributeIdent::Keyword,
+        AttributeIdent::Tags,
         AttributeIdent::ScenePath,
+        AttributeIdent::Scene,
+        AttributeIdent::Xfail,
+        AttributeIdent::Retries,
+        AttributeIdent::Setup,
+        AttributeIdent::Teardown,
     ])? {
         match ident {
             AttributeIdent::Focus => {
@@ -47,6 +62,17 @@ pub fn attribute_gditest(input_decl: Declaration) -> Result<TokenStream, Error>
             }
             AttributeIdent::Skip => {
                 skipped = true;
+                if parser.is_next_equal_sign() {
+                    parser.pop_equal_sign()?;
+                    let reason_lit = parser.get_literal()?;
+                    skip_reason = quote! { Some( #reason_lit ) };
+                }
+                parser.progress_puct();
+            }
+            AttributeIdent::SkipIf => {
+                parser.pop_equal_sign()?;
+                let condition = parser.get_skip_condition(quote! { ::gd_rehearse::itest })?;
+                skip_condition = quote! { Some( #condition ) };
                 parser.progress_puct();
             }
             AttributeIdent::Keyword => {
@@ -55,10 +81,47 @@ pub fn attribute_gditest(input_decl: Declaration) -> Result<TokenStream, Error>
                 keyword = quote! { Some(#keyword_literal) };
                 parser.progress_puct();
             }
+            AttributeIdent::Tags => {
+                parser.pop_equal_sign()?;
+                tags = parser.get_literal_array()?;
+                parser.progress_puct();
+            }
             AttributeIdent::ScenePath => {
                 parser.pop_equal_sign()?;
-                let scene_path_lit = parser.get_literal_scene_path()?;
-                scene_path = quote! { Some( #scene_path_lit ) }
+                let scene_path_lit = parser.get_literal_scene_path(AttributeIdent::ScenePath)?;
+                scene_path = quote! { Some( #scene_path_lit ) };
+                parser.progress_puct();
+            }
+            AttributeIdent::Scene => {
+                parser.pop_equal_sign()?;
+                let scene_lit = parser.get_literal_scene_path(AttributeIdent::Scene)?;
+                scene = quote! { Some( #scene_lit ) };
+                parser.progress_puct();
+            }
+            AttributeIdent::Xfail => {
+                parser.pop_equal_sign()?;
+                let reason_lit = parser.get_literal()?;
+                xfail = quote! { Some( #reason_lit ) };
+                parser.progress_puct();
+            }
+            AttributeIdent::Retries => {
+                parser.pop_equal_sign()?;
+                let retries_lit = parser.get_literal()?;
+                retries = retries_lit
+                    .to_string()
+                    .parse::<u32>()
+                    .map_err(|_| venial::Error::new_at_tokens(retries_lit, "expected integer"))?;
+                parser.progress_puct();
+            }
+            AttributeIdent::Setup => {
+                parser.pop_equal_sign()?;
+                setup_function = Some(parser.get_ident()?);
+                parser.progress_puct();
+            }
+            AttributeIdent::Teardown => {
+                parser.pop_equal_sign()?;
+                teardown_function = Some(parser.get_ident()?);
+                parser.progress_puct();
             }
             _ => unreachable!(),
         }
@@ -71,6 +134,18 @@ pub fn attribute_gditest(input_decl: Declaration) -> Result<TokenStream, Error>
         );
     }
 
+    let setup_function = if let Some(setup) = setup_function {
+        quote! { Some(#setup) }
+    } else {
+        quote! { None }
+    };
+
+    let teardown_function = if let Some(teardown) = teardown_function {
+        quote! { Some(#teardown) }
+    } else {
+        quote! { None }
+    };
+
     let test_name = &func.name;
     let test_name_str = func.name.to_string();
 
@@ -106,12 +181,21 @@ pub fn attribute_gditest(input_decl: Declaration) -> Result<TokenStream, Error>
         ::godot::sys::plugin_add!(gd_rehearse::itest::GD_REHEARSE_RUST_TEST_CASES; gd_rehearse::itest::RustTestCase {
             name: #test_name_str,
             skipped: #skipped,
+            skip_reason: #skip_reason,
+            skip_condition: #skip_condition,
             focused: #focused,
             keyword: #keyword,
+            tags: &[#(#tags),*],
             file: std::file!(),
+            module_path: std::module_path!(),
             line: std::line!(),
             function: #test_name,
-            scene_path: #scene_path
+            scene_path: #scene_path,
+            scene: #scene,
+            xfail: #xfail,
+            retries: #retries,
+            setup_function: #setup_function,
+            teardown_function: #teardown_function
         });
     })
 }
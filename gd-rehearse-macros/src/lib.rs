@@ -10,6 +10,7 @@ use utils::translate_meta;
 mod bench;
 mod itest;
 pub(crate) mod parser;
+mod suite;
 mod utils;
 
 /// Integration test between Godot and Rust.
@@ -23,18 +24,35 @@ mod utils;
 /// - Have no return values.
 /// - Have no parameters or only a singular [`TestContext`](gd_rehearse_defs::cases::rust_test_case::TestContext).
 ///
+/// Tests are identified by their qualified name, made from the path of the declaring module and the function name (eg.
+/// `my_crate::physics::setup_works`). It is used in the runner output and matched against the runner filters.
+///
 /// ## Attributes
 /// An attribute-less macro will make the tests run, but some attributes are available for better customizability, especially when working
 /// on specific attributes and creating more narrow test runner scenes.
 ///
-/// - `skip`: Skips the test during run.
+/// - `skip`: Skips the test during run. Reason of the skip can be provided with `skip = "reason"`, to be printed next to the skipped test.
+/// - `skip_if`: Skips the test if a condition is met during run. Either one of `"headless"`, `"editor"`, `"godot_release"`, `"godot_debug"`,
+///   `"rust_release"`, `"rust_debug"`, or a name of function with `fn(&RunnerInfo) -> bool` signature.
 /// - `focus`: Forces focus run, in which only tests annotated with `focus` will be run.
 /// - `keyword`: A specific keyword that will be picked up by the runner, and the test will be run only if the runner has the same keyword specified.
+/// - `tags`: An array of tags, eg. `tags = ["physics", "slow"]`. Runner can be configured to run only tests with some of the tags, or to
+///   exclude them.
 /// - `scene_path`: Godot path to the scene. If specified, given benchmark will only run if runner's scene path is the same.
+/// - `scene`: Godot path to the scene which will be instantiated under the runner before the test, and freed after it. Its root is
+///   accessible with `TestContext::scene()`. Other scenes can be instantiated during the test with `TestContext::instantiate_scene()`.
+/// - `xfail`: Marks the test as expected to fail, eg. `xfail = "engine bug #1234"`. Its failure won't fail the run, but it passing will -
+///   helpful for tracking known bugs, to be notified when they are fixed.
+/// - `retries`: Number of times the test will be rerun if it fails, eg. `retries = 3`. Test passing after being rerun is reported as flaky.
+/// - `setup`: Optional function with `fn(&mut TestContext)` signature that will be executed before the test, to set up the scene for it.
+/// - `teardown`: Optional function with `fn(&mut TestContext)` signature that will be executed after the test, even if the test panicked.
+///   By default all nodes added with `TestContext::setup_add_node()` are removed, so it is needed only for additional cleanup - custom
+///   teardown needs to remove them by itself.
 ///
 /// ## Examples
 /// ```no_run
 /// use gd_rehearse::itest::*;
+/// use godot::prelude::*;
 ///
 /// // Causes a focus run during which only the focused tests will be executed, but only with
 /// // `my test` as a keyword in the runner.
@@ -50,6 +68,28 @@ mod utils;
 ///     assert_eq!(test, 1);
 /// }
 ///
+/// // Will be skipped only when running from the command line, with reason printed.
+/// #[gditest(skip_if = "headless")]
+/// fn editor_only_test() {
+///     assert!(true);
+/// }
+///
+/// fn is_release(info: &RunnerInfo) -> bool {
+///     !info.is_rust_debug() || !info.is_godot_debug()
+/// }
+///
+/// // Will be skipped if the custom condition is met.
+/// #[gditest(skip_if = is_release)]
+/// fn debug_only_test() {
+///     assert!(true);
+/// }
+///
+/// // Can be selected or excluded by the runner with its tags.
+/// #[gditest(tags = ["physics", "slow"])]
+/// fn tagged_test() {
+///     assert!(true);
+/// }
+///
 /// // Can access the `GdTestRunner` scene_tree.
 /// #[gditest]
 /// fn test_with_ctx(ctx: &TestContext) {
@@ -62,11 +102,29 @@ mod utils;
 ///     let test_node = ctx.scene_tree().get_node_or_null("SomeTestNode");
 ///     assert!(test_node.is_some());
 ///     assert!(!test_node.unwrap().get("property_should_be_here").is_nil());
-/// 
+///
 ///     // Shorthand for getting node by name.
 ///     let test_node = ctx.get_node("SomeTestNode");
 ///     assert!(!test_node.get("property_should_be_here").is_nil());
 /// }  
+///
+/// // `res://level.tscn` will be instantiated before the test and freed after it.
+/// #[gditest(scene = "res://level.tscn")]
+/// fn test_with_scene(ctx: &TestContext) {
+///     let level = ctx.scene();
+///     let _player = level.get_node_or_null("Player").unwrap();
+/// }
+///
+/// fn setup_node(ctx: &mut TestContext) {
+///     ctx.setup_add_node(Node::new_alloc(), "SetupNode");
+/// }
+///
+/// // Node added during setup will be removed after the test, even if it panics.
+/// #[gditest(setup = setup_node)]
+/// fn test_with_setup(ctx: &TestContext) {
+///     let node = ctx.get_setup_node("SetupNode");
+///     assert_eq!(node.get_name(), "SetupNode".into());
+/// }
 /// ```
 #[proc_macro_attribute]
 pub fn gditest(meta: TokenStream, input: TokenStream) -> TokenStream {
@@ -82,6 +140,9 @@ pub fn gditest(meta: TokenStream, input: TokenStream) -> TokenStream {
 /// - Have a return value.
 /// - Have no parameters or only a singular [`BenchContext`](gd_rehearse_defs::cases::rust_bench::BenchContext).
 ///
+/// Benchmarks are identified by their qualified name, made from the path of the declaring module and the function name (eg.
+/// `my_crate::physics::bench_collision`). It is used in the runner output and matched against the runner filters.
+///
 /// Every benchmark is executed 200 times for a *warm-up*, followed by 501 additional runs to assess runtime (an odd number of runs for easy
 /// median extraction). Minimum and median run times will be displayed.
 ///
@@ -89,10 +150,17 @@ pub fn gditest(meta: TokenStream, input: TokenStream) -> TokenStream {
 /// An attribute-less macro will make the benchmark run, but several attributes are available for better customizability, especially when
 /// working on specific attributes and creating more narrowly-focused test runner scenes.
 ///
-/// - `skip`: Skips the benchmark during execution.
+/// - `skip`: Skips the benchmark during execution. Reason of the skip can be provided with `skip = "reason"`, to be printed next to the
+///   skipped benchmark.
+/// - `skip_if`: Skips the benchmark if a condition is met during run. Either one of `"headless"`, `"editor"`, `"godot_release"`,
+///   `"godot_debug"`, `"rust_release"`, `"rust_debug"`, or a name of function with `fn(&RunnerInfo) -> bool` signature.
 /// - `focus`: Forces a focused run, in which only benchmarks annotated with `focus` will be executed.
 /// - `keyword`: A specific keyword that will be picked up by the runner. The benchmark runs only if the runner has the same keyword specified.
+/// - `tags`: An array of tags, eg. `tags = ["physics", "slow"]`. Runner can be configured to run only benchmarks with some of the tags, or
+///   to exclude them.
 /// - `scene_path`: Godot path to the scene. If specified, given benchmark will only run if runner's scene path is the same.
+/// - `scene`: Godot path to the scene which will be instantiated under the runner before the benchmark, and freed after it. Its root
+///   is accessible with `BenchContext::scene()`.
 /// - `repeat`: Specifies the number of internal repeats the benchmark should undergo. By default, the function executes 100 times within every run.
 /// - `setup`: Optional function that will be executed before benchmark execution, to set up the scene for benchmarks.
 /// - `cleanup`: Optional function that will be executed after benchmark execution, to clean up after benchmarks. Rarely needed, as when
@@ -135,3 +203,41 @@ pub fn gditest(meta: TokenStream, input: TokenStream) -> TokenStream {
 pub fn gdbench(meta: TokenStream, input: TokenStream) -> TokenStream {
     translate_meta("gdbench", meta, input, bench::attribute_bench)
 }
+
+/// Shared fixtures for all [`#[gditest]`](macro@gditest) tests declared in the same file.
+///
+/// Useful when many tests need the same expensive scene to be prepared. Only one `gdsuite!` can be declared per file.
+///
+/// - `before_all`: Function with `fn(&mut TestContext)` signature, executed once before the first test from the file. Nodes added with
+///   `TestContext::setup_add_node()` are kept in the scene until the last test from the file finishes. If it panics, all tests from the
+///   file are marked as failed without being run.
+/// - `after_all`: Function with `fn(&mut TestContext)` signature, executed once after the last test from the file. By default all nodes
+///   added in `before_all` are removed, so it is needed only for additional cleanup - custom `after_all` needs to remove them by itself.
+///   Its failure is reported on the last test from the file.
+///
+/// Fixtures are executed only if at least one test from the file is run. Nodes added in `before_all` are retrieved with
+/// `TestContext::get_suite_node()`, which unlike `get_node()` finds them also when the cases are isolated with their own root.
+///
+/// ## Examples
+/// ```no_run
+/// use gd_rehearse::itest::*;
+/// use godot::prelude::*;
+///
+/// fn load_level(ctx: &mut TestContext) {
+///     let level = load::<PackedScene>("res://level.tscn").instantiate().unwrap();
+///     ctx.setup_add_node(level, "Level");
+/// }
+///
+/// gdsuite!(before_all = load_level);
+///
+/// #[gditest]
+/// fn level_has_player(ctx: &TestContext) {
+///     let _player = ctx.get_suite_node("Level/Player");
+/// }
+/// ```
+#[proc_macro]
+pub fn gdsuite(input: TokenStream) -> TokenStream {
+    suite::macro_gdsuite(input.into())
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -4,7 +4,8 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
 */
 
-use proc_macro2::{Ident, Literal, TokenTree};
+use proc_macro2::{Delimiter, Ident, Literal, TokenStream, TokenTree};
+use quote::quote;
 use std::collections::VecDeque;
 use venial::{Attribute, AttributeValue};
 
@@ -17,10 +18,18 @@ pub enum AttributeIdent {
     Repeat,
     Focus,
     Skip,
+    SkipIf,
     Keyword,
+    Tags,
     ScenePath,
+    Scene,
+    Xfail,
+    Retries,
     Setup,
     Cleanup,
+    Teardown,
+    BeforeAll,
+    AfterAll,
 }
 
 impl AttributeIdent {
@@ -29,10 +38,18 @@ impl AttributeIdent {
             "repeat" => Some(Self::Repeat),
             "focus" => Some(Self::Focus),
             "skip" => Some(Self::Skip),
+            "skip_if" => Some(Self::SkipIf),
             "keyword" => Some(Self::Keyword),
+            "tags" => Some(Self::Tags),
             "scene_path" => Some(Self::ScenePath),
+            "scene" => Some(Self::Scene),
+            "xfail" => Some(Self::Xfail),
+            "retries" => Some(Self::Retries),
             "setup" => Some(Self::Setup),
             "cleanup" => Some(Self::Cleanup),
+            "teardown" => Some(Self::Teardown),
+            "before_all" => Some(Self::BeforeAll),
+            "after_all" => Some(Self::AfterAll),
             _ => None,
         }
     }
@@ -42,10 +59,18 @@ impl AttributeIdent {
             AttributeIdent::Repeat => "repeat".to_owned(),
             AttributeIdent::Focus => "focus".to_owned(),
             AttributeIdent::Skip => "skip".to_owned(),
+            AttributeIdent::SkipIf => "skip_if".to_owned(),
             AttributeIdent::Keyword => "keyword".to_owned(),
+            AttributeIdent::Tags => "tags".to_owned(),
             AttributeIdent::ScenePath => "scene_path".to_owned(),
+            AttributeIdent::Scene => "scene".to_owned(),
+            AttributeIdent::Xfail => "xfail".to_owned(),
+            AttributeIdent::Retries => "retries".to_owned(),
             AttributeIdent::Setup => "setup".to_owned(),
             AttributeIdent::Cleanup => "cleanup".to_owned(),
+            AttributeIdent::Teardown => "teardown".to_owned(),
+            AttributeIdent::BeforeAll => "before_all".to_owned(),
+            AttributeIdent::AfterAll => "after_all".to_owned(),
         }
     }
 
@@ -104,6 +129,10 @@ impl AttributeValueParser {
         Err(venial::Error::new("expected equal sign"))
     }
 
+    pub fn is_next_equal_sign(&self) -> bool {
+        matches!(self.tokens.front(), Some(TokenTree::Punct(punct)) if punct.as_char() == '=')
+    }
+
     pub fn get_literal(&mut self) -> Result<Literal, venial::Error> {
         if let Some(token) = self.tokens.pop_front() {
             if let TokenTree::Literal(literal) = token {
@@ -114,6 +143,31 @@ impl AttributeValueParser {
         Err(venial::Error::new("expected literal"))
     }
 
+    pub fn get_literal_array(&mut self) -> Result<Vec<Literal>, venial::Error> {
+        if let Some(token) = self.tokens.pop_front() {
+            if let TokenTree::Group(group) = &token {
+                if group.delimiter() == Delimiter::Bracket {
+                    let mut literals = Vec::new();
+                    for inner in group.stream() {
+                        match inner {
+                            TokenTree::Literal(literal) => literals.push(literal),
+                            TokenTree::Punct(punct) if punct.as_char() == ',' => {}
+                            other => {
+                                return Err(venial::Error::new_at_tokens(other, "expected literal"))
+                            }
+                        }
+                    }
+                    return Ok(literals);
+                }
+            }
+            return Err(venial::Error::new_at_tokens(
+                token,
+                "expected array of literals",
+            ));
+        }
+        Err(venial::Error::new("expected array of literals"))
+    }
+
     pub fn get_ident(&mut self) -> Result<Ident, venial::Error> {
         if let Some(token) = self.tokens.pop_front() {
             if let TokenTree::Ident(ident) = token {
@@ -150,7 +204,41 @@ impl AttributeValueParser {
             _ = self.tokens.pop_front();
         }
     }
-    pub fn get_literal_scene_path(&mut self) -> Result<Literal, venial::Error> {
+
+    /// Gets either one of the predefined conditions as a literal, or a name of user function, as `SkipCondition` expression.
+    pub fn get_skip_condition(&mut self, api: TokenStream) -> Result<TokenStream, venial::Error> {
+        match self.tokens.front() {
+            Some(TokenTree::Literal(_)) => {
+                let literal = self.get_literal()?;
+                let variant = match literal.to_string().trim_matches('"') {
+                    "headless" => quote! { Headless },
+                    "editor" => quote! { Editor },
+                    "godot_release" => quote! { GodotRelease },
+                    "godot_debug" => quote! { GodotDebug },
+                    "rust_release" => quote! { RustRelease },
+                    "rust_debug" => quote! { RustDebug },
+                    _ => {
+                        return Err(venial::Error::new_at_tokens(
+                            literal,
+                            "`skip_if` needs to be a function or one of: `headless`, `editor`, `godot_release`, \
+                            `godot_debug`, `rust_release`, `rust_debug`",
+                        ))
+                    }
+                };
+                Ok(quote! { #api::SkipCondition::#variant })
+            }
+            _ => {
+                let condition = self.get_ident()?;
+                let name = condition.to_string();
+                Ok(quote! { #api::SkipCondition::Custom { name: #name, condition: #condition } })
+            }
+        }
+    }
+
+    pub fn get_literal_scene_path(
+        &mut self,
+        ident: AttributeIdent,
+    ) -> Result<Literal, venial::Error> {
         let literal = self.get_literal()?;
         let literal_as_str = &literal.to_string();
         let start_correct = literal_as_str.starts_with("\"res://");
@@ -158,8 +246,12 @@ impl AttributeValueParser {
         if start_correct && end_correct {
             return Ok(literal);
         }
-        Err(venial::Error::new(
-            "`scene_path` needs to start with `res://` and end with `.tscn`",
+        Err(venial::Error::new_at_tokens(
+            literal,
+            format!(
+                "`{}` needs to start with `res://` and end with `.tscn`",
+                ident.to_str()
+            ),
         ))
     }
 }
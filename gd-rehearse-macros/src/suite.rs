@@ -0,0 +1,63 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+use crate::parser::{AttributeIdent, AttributeValueParser};
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use venial::Error;
+
+pub fn macro_gdsuite(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut before_all: Option<Ident> = None;
+    let mut after_all: Option<Ident> = None;
+
+    let mut parser = AttributeValueParser::from_tokens(input.into_iter().collect());
+
+    while let Some(ident) =
+        parser.get_one_of_idents(&[AttributeIdent::BeforeAll, AttributeIdent::AfterAll])?
+    {
+        match ident {
+            AttributeIdent::BeforeAll => {
+                parser.pop_equal_sign()?;
+                before_all = Some(parser.get_ident()?);
+                parser.progress_puct();
+            }
+            AttributeIdent::AfterAll => {
+                parser.pop_equal_sign()?;
+                after_all = Some(parser.get_ident()?);
+                parser.progress_puct();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    if before_all.is_none() && after_all.is_none() {
+        return Err(Error::new(
+            "gdsuite!: at least one of `before_all` or `after_all` needs to be provided",
+        ));
+    }
+
+    let before_all = if let Some(before_all) = before_all {
+        quote! { Some(#before_all) }
+    } else {
+        quote! { None }
+    };
+
+    let after_all = if let Some(after_all) = after_all {
+        quote! { Some(#after_all) }
+    } else {
+        quote! { None }
+    };
+
+    Ok(quote! {
+        ::godot::sys::plugin_add!(gd_rehearse::itest::GD_REHEARSE_RUST_TEST_SUITES; gd_rehearse::itest::RustTestSuite {
+            file: std::file!(),
+            line: std::line!(),
+            before_all: #before_all,
+            after_all: #after_all
+        });
+    })
+}
@@ -15,16 +15,28 @@ pub use gd_rehearse_defs::runner::GdTestRunner;
 
 /// Contains all symbols necessary to use [`#[gditest]`](macro@gd_rehearse_macros::gditest) macro.
 pub mod itest {
+    pub use gd_rehearse_defs::assertions::{ApproxComponents, DEFAULT_EPSILON};
+    pub use gd_rehearse_defs::cases::input::InputSimulator;
     pub use gd_rehearse_defs::cases::rust_test_case::{RustTestCase, TestContext};
-    pub use gd_rehearse_defs::cases::CaseContext;
+    pub use gd_rehearse_defs::cases::rust_test_suite::RustTestSuite;
+    pub use gd_rehearse_defs::cases::signal_spy::SignalSpy;
+    pub use gd_rehearse_defs::cases::{CaseContext, SkipCondition};
+    pub use gd_rehearse_defs::property::{generators, Generator, Rng};
     pub use gd_rehearse_defs::registry::itest::*;
-    pub use gd_rehearse_macros::gditest;
+    pub use gd_rehearse_defs::runner::config::RunnerInfo;
+    pub use gd_rehearse_defs::snapshot::{snapshot_node_tree, snapshot_resource};
+    pub use gd_rehearse_defs::{
+        assert_approx_eq, assert_dict_contains, assert_snapshot, assert_variant_eq, expect,
+        expect_eq,
+    };
+    pub use gd_rehearse_macros::{gditest, gdsuite};
 }
 
 /// Contains all symbols necessary to use [`#[gdbench]`](macro@gd_rehearse_macros::gdbench) macro.
 pub mod bench {
     pub use gd_rehearse_defs::cases::rust_bench::{bench_used, BenchContext, RustBenchmark};
-    pub use gd_rehearse_defs::cases::CaseContext;
+    pub use gd_rehearse_defs::cases::{CaseContext, SkipCondition};
     pub use gd_rehearse_defs::registry::bench::*;
+    pub use gd_rehearse_defs::runner::config::RunnerInfo;
     pub use gd_rehearse_macros::gdbench;
 }
@@ -15,11 +15,21 @@ fn skipped_bench() -> i32 {
     234
 }
 
+#[gdbench(skip = "too slow")]
+fn skipped_with_reason_bench() -> i32 {
+    423
+}
+
 #[gdbench]
 fn normal_bench() -> i32 {
     324
 }
 
+#[gdbench(tags = ["slow"])]
+fn tagged_bench() -> i32 {
+    432
+}
+
 #[gdbench(keyword = "with ctx")]
 fn bench_with_ctx(ctx: &BenchContext) -> InstanceId {
     let gd: Gd<Object> = ctx.scene_tree().clone().upcast();
@@ -33,6 +43,12 @@ fn path_bench(ctx: &BenchContext) -> i32 {
     value.to::<i32>() + 321_i32
 }
 
+#[gdbench(scene = "res://instanced.tscn")]
+fn scene_bench(ctx: &BenchContext) -> i32 {
+    let some_node = ctx.scene().get_node_or_null("SomeNode").unwrap();
+    some_node.get("my_value").to::<i32>()
+}
+
 #[gdbench(scene_path = "res://nonexistent.tscn")]
 fn shouldnt_run_path() -> i8 {
     let test = 1 + 1;
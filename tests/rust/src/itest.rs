@@ -4,9 +4,17 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
 */
 
+use std::sync::atomic::{AtomicU32, Ordering};
+
 use gd_rehearse::itest::*;
-use godot::classes::Object;
-use godot::obj::Gd;
+use godot::builtin::{varray, vdict, GString, Vector2, Vector3};
+use godot::classes::{
+    Engine, INode, Input, InputEvent, InputEventKey, InputMap, Node, Node2D, Object, Resource,
+};
+use godot::global::{JoyAxis, Key};
+use godot::meta::ToGodot;
+use godot::obj::{Base, Gd, NewAlloc, NewGd};
+use godot::register::{godot_api, GodotClass};
 
 #[gditest]
 fn simple_test() {
@@ -29,6 +37,63 @@ fn skipped_test() {
     assert_eq!(test, 1);
 }
 
+#[gditest(skip = "known to fail")]
+fn skipped_with_reason() {
+    let test = 1 + 1;
+    assert_eq!(test, 1);
+}
+
+#[gditest(skip_if = "godot_release")]
+fn skipped_in_godot_release() {
+    assert!(godot::classes::Os::singleton().is_debug_build());
+}
+
+fn never(_info: &RunnerInfo) -> bool {
+    false
+}
+
+#[gditest(skip_if = never)]
+fn not_skipped_by_custom_condition() {}
+
+#[gditest(xfail = "known to fail")]
+fn expected_failure() {
+    let test = 1 + 1;
+    assert_eq!(test, 1);
+}
+
+static FLAKY_ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+
+#[gditest(retries = 2)]
+fn flaky_test() {
+    let attempt = FLAKY_ATTEMPTS.fetch_add(1, Ordering::Relaxed);
+    assert!(attempt > 0, "fails on the first attempt");
+}
+
+fn setup_function(ctx: &mut TestContext) {
+    let mut node = Node::new_alloc();
+    let mut child = Node::new_alloc();
+    child.set_name("SetupChild");
+    node.add_child(&child);
+
+    ctx.setup_add_node(node, "SetupTest");
+}
+
+fn teardown_function(ctx: &mut TestContext) {
+    ctx.remove_added_node("SetupTest");
+}
+
+#[gditest(setup = setup_function)]
+fn with_setup(ctx: &TestContext) {
+    let setup = ctx.get_setup_node("SetupTest");
+    assert_eq!(setup.get_child_count(), 1);
+    let _child = ctx.get_node("SetupTest/SetupChild");
+}
+
+#[gditest(setup = setup_function, teardown = teardown_function)]
+fn with_setup_and_teardown(ctx: &TestContext) {
+    let _setup = ctx.get_setup_node_as::<Node>("SetupTest");
+}
+
 #[gditest(keyword = "with ctx")]
 fn test_with_ctx(ctx: &TestContext) {
     let gd: Gd<Object> = ctx.scene_tree().clone().upcast();
@@ -38,6 +103,175 @@ fn test_with_ctx(ctx: &TestContext) {
 #[gditest(keyword = "my new class")]
 fn keyword_class_test() {}
 
+#[gditest(tags = ["physics", "slow"])]
+fn tagged_test() {}
+
+// Excluded by `res://gd_rehearse.cfg`, unless the tag is selected on the command line, which takes precedence.
+#[gditest(tags = ["excluded_by_config_file"])]
+fn excluded_by_config_file() {
+    panic!("should be excluded by `res://gd_rehearse.cfg`");
+}
+
+#[gditest(scene_path = "res://with_path.tscn", tags = ["slow"])]
+fn tagged_with_path(ctx: &TestContext) {
+    assert_eq!(
+        ctx.scene_tree().get_scene_file_path().to_string(),
+        "res://with_path.tscn"
+    )
+}
+
+#[gditest]
+fn signal_spy(ctx: &TestContext) {
+    let spy = ctx.watch_signal(ctx.scene_tree(), "child_entered_tree");
+    spy.assert_not_emitted();
+
+    let mut node = Node::new_alloc();
+    ctx.scene_tree().clone().add_child(&node);
+    spy.assert_emitted_times(1);
+    spy.assert_emitted_with(&[node.to_variant()]);
+    spy.assert_last_emitted_with(&[node.to_variant()]);
+
+    node.queue_free();
+}
+
+#[gditest]
+fn signal_spy_failure_messages(ctx: &TestContext) {
+    let mut object = Object::new_alloc();
+    object.add_user_signal("scored");
+    let spy = ctx.watch_signal(&object, "scored");
+    object.emit_signal(
+        "scored",
+        &[10.to_variant(), GString::from("hit").to_variant()],
+    );
+    object.emit_signal(
+        "scored",
+        &[20.to_variant(), GString::from("miss").to_variant()],
+    );
+
+    let signal = format!("signal `scored` of `Object::{}`", object.instance_id());
+    let args = |score: i32, kind: &str| {
+        format!(
+            "({:?}, {:?})",
+            score.to_variant(),
+            GString::from(kind).to_variant()
+        )
+    };
+    let recorded = format!(
+        "  recorded:\n    #1: {}\n    #2: {}",
+        args(10, "hit"),
+        args(20, "miss")
+    );
+
+    assert_eq!(
+        panic_message(
+            || spy.assert_emitted_with(&[30.to_variant(), GString::from("hit").to_variant()])
+        ),
+        format!(
+            "{signal} wasn't emitted with expected arguments\n  expected: {}\n{recorded}",
+            args(30, "hit")
+        )
+    );
+    assert_eq!(
+        panic_message(|| spy.assert_emitted_times(1)),
+        format!("{signal} was emitted 2 times, expected 1\n{recorded}")
+    );
+
+    drop(spy);
+    object.free();
+}
+
+#[gditest]
+fn simulated_input(ctx: &TestContext) {
+    let mut input_map = InputMap::singleton();
+    input_map.add_action("gd_rehearse_action");
+
+    ctx.input().press_action("gd_rehearse_action");
+    assert!(Input::singleton().is_action_pressed("gd_rehearse_action"));
+    ctx.input().release_action("gd_rehearse_action");
+    assert!(!Input::singleton().is_action_pressed("gd_rehearse_action"));
+
+    // Held key is released after the test.
+    ctx.input().press_key(Key::SPACE);
+    assert!(Input::singleton().is_key_pressed(Key::SPACE));
+
+    input_map.erase_action("gd_rehearse_action");
+
+    // Moved axis is returned to `0.0` after the test.
+    ctx.input().move_joypad_axis(0, JoyAxis::LEFT_X, 0.5);
+    assert_eq!(Input::singleton().get_joy_axis(0, JoyAxis::LEFT_X), 0.5);
+}
+
+/// Records input events received by its `_input` and `_unhandled_input` handlers.
+#[derive(GodotClass)]
+#[class(init, base = Node)]
+struct InputRecorder {
+    input: Vec<Gd<InputEvent>>,
+    unhandled_input: Vec<Gd<InputEvent>>,
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for InputRecorder {
+    fn input(&mut self, event: Gd<InputEvent>) {
+        self.input.push(event);
+    }
+
+    fn unhandled_input(&mut self, event: Gd<InputEvent>) {
+        self.unhandled_input.push(event);
+    }
+}
+
+#[gditest]
+fn simulated_input_reaches_handlers(ctx: &TestContext) {
+    let recorder = InputRecorder::new_alloc();
+    ctx.scene_tree().clone().add_child(&recorder);
+
+    ctx.input().tap_key(Key::ENTER);
+
+    let is_enter_key = |event: &Gd<InputEvent>, pressed: bool| {
+        event
+            .clone()
+            .try_cast::<InputEventKey>()
+            .is_ok_and(|key| key.get_keycode() == Key::ENTER && key.is_pressed() == pressed)
+    };
+    for events in [&recorder.bind().input, &recorder.bind().unhandled_input] {
+        assert_eq!(events.len(), 2);
+        assert!(is_enter_key(&events[0], true));
+        assert!(is_enter_key(&events[1], false));
+    }
+
+    recorder.free();
+}
+
+#[gditest]
+fn advance_physics_ticks(ctx: &TestContext) {
+    ctx.set_physics_ticks_per_second(120);
+    let start = Engine::singleton().get_physics_frames();
+
+    ctx.advance_physics_ticks(5, move |ctx| {
+        assert_eq!(Engine::singleton().get_physics_frames() - start, 5);
+        assert_eq!(Engine::singleton().get_physics_ticks_per_second(), 120);
+
+        ctx.advance_physics_ticks(1, move |_ctx| {
+            assert_eq!(Engine::singleton().get_physics_frames() - start, 6);
+        });
+    });
+}
+
+#[gditest(scene = "res://instanced.tscn")]
+fn with_scene(ctx: &TestContext) {
+    let scene = ctx.scene();
+    assert_eq!(scene.get_name(), "Instanced".into());
+    let value = scene.get_node_or_null("SomeNode").unwrap().get("my_value");
+    assert_eq!(value.to::<i32>(), 344);
+}
+
+#[gditest]
+fn instantiate_scene(ctx: &TestContext) {
+    let scene = ctx.instantiate_scene::<Node>("res://instanced.tscn");
+    assert!(scene.get_node_or_null("SomeNode").is_some());
+}
+
 #[gditest]
 fn filter_me() {}
 
@@ -60,6 +294,24 @@ fn with_test_path(ctx: &TestContext) {
     )
 }
 
+// Run at the end of the scenes sequence in CI, with `--scenes=res://test.tscn,res://with_path.tscn,res://sequence.tscn`.
+#[gditest(scene_path = "res://sequence.tscn")]
+fn scenes_sequence_replaces_runner(ctx: &TestContext) {
+    let tree = ctx
+        .scene_tree()
+        .get_tree()
+        .expect("runner should be inside tree");
+    assert_eq!(tree.get_current_scene().as_ref(), Some(ctx.scene_tree()));
+    let runners = tree
+        .get_root()
+        .expect("tree should have root")
+        .get_children()
+        .iter_shared()
+        .filter(|node| node.is_class("GdTestRunner"))
+        .count();
+    assert_eq!(runners, 1, "runners of the previous scenes should be freed");
+}
+
 #[gditest(scene_path = "res://nonexistent.tscn")]
 fn shouldnt_run_path() {
     let test = 1 + 1;
@@ -75,3 +327,241 @@ fn access_ctx_with_path(ctx: &TestContext) {
     let val_as_int = value.to::<i32>();
     assert_eq!(val_as_int, 344);
 }
+
+mod nested {
+    use gd_rehearse::itest::*;
+
+    // Same name as the test in parent module - distinguishable by its qualified name.
+    #[gditest]
+    fn simple_test() {
+        let test = 2 * 2;
+        assert_eq!(test, 4);
+    }
+}
+
+#[gditest(scene_path = "res://isolated.tscn")]
+fn isolated_case_root(ctx: &TestContext) {
+    assert_eq!(ctx.scene_tree().get_name(), "CaseRoot".into());
+    assert_eq!(ctx.scene_tree().get_child_count(), 0);
+    ctx.scene_tree().clone().add_child(&Node::new_alloc());
+}
+
+#[gditest(scene_path = "res://isolated.tscn")]
+fn isolated_previous_nodes_freed(ctx: &TestContext) {
+    assert_eq!(ctx.scene_tree().get_child_count(), 0);
+}
+
+#[gditest]
+fn godot_assertions() {
+    let saved = vdict! { "hp": 10, "items": varray!["sword", "shield"] };
+    assert_variant_eq!(
+        saved,
+        vdict! { "hp": 10, "items": varray!["sword", "shield"] }
+    );
+    assert_dict_contains!(saved, { "hp": 10 });
+
+    let rotated = Vector3::RIGHT.rotated(Vector3::UP, std::f32::consts::FRAC_PI_2);
+    assert_approx_eq!(rotated, Vector3::FORWARD);
+    assert_approx_eq!(1.0_f32, 1.05, epsilon = 0.1);
+}
+
+#[gditest]
+fn godot_assertions_diff() {
+    let message = panic_message(|| {
+        let saved = vdict! { "hp": 10, "items": varray!["sword"] };
+        assert_variant_eq!(saved, vdict! { "hp": 12, "items": varray!["axe"] });
+    });
+    let differences = message
+        .split_once("differences:")
+        .expect("differences should be listed")
+        .1;
+    let hp = differences
+        .lines()
+        .find(|line| line.contains("hp"))
+        .expect("`hp` should be listed");
+    assert!(hp.contains("10") && hp.contains("12"), "{hp}");
+    let item = differences
+        .lines()
+        .find(|line| line.contains("items"))
+        .expect("`items` should be listed");
+    assert!(
+        item.contains("][0]:") && item.contains("sword") && item.contains("axe"),
+        "{item}"
+    );
+}
+
+#[gditest]
+fn approx_assertions_fail_on_nan() {
+    panic_message(|| assert_approx_eq!(f32::NAN, 1.0));
+    panic_message(|| assert_approx_eq!(Vector2::new(f32::NAN, 0.0), Vector2::ZERO));
+}
+
+/// Runs `code` which is expected to panic, returning the panic message.
+fn panic_message(code: impl FnOnce()) -> String {
+    let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(code))
+        .expect_err("code should panic");
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|str| str.to_string()))
+        .unwrap_or_default()
+}
+
+#[gditest]
+fn node_tree_snapshot() {
+    let mut root = Node::new_alloc();
+    root.set_name("Root");
+    let mut child = Node2D::new_alloc();
+    child.set_name("Child");
+    child.set_position(Vector2::new(1.0, 2.0));
+    root.add_child(&child);
+
+    let snapshot = snapshot_node_tree(&root, &["position"]);
+    root.free();
+    assert_snapshot!("node_tree", snapshot);
+}
+
+fn updating_snapshots(info: &RunnerInfo) -> bool {
+    info.is_updating_snapshots()
+}
+
+// Skipped when updating the snapshots, as the stored one would be overwritten instead of compared.
+#[gditest(skip_if = updating_snapshots)]
+fn node_tree_snapshot_mismatch() {
+    let mut root = Node::new_alloc();
+    root.set_name("Root");
+    let mut child = Node2D::new_alloc();
+    child.set_name("Child");
+    child.set_position(Vector2::new(3.0, 4.0));
+    root.add_child(&child);
+
+    let snapshot = snapshot_node_tree(&root, &["position"]);
+    root.free();
+    let message = panic_message(|| assert_snapshot!("node_tree", snapshot));
+    assert!(
+        message.starts_with("snapshot `node_tree` doesn't match"),
+        "{message}"
+    );
+    assert!(
+        message.ends_with(
+            "  --- stored\n  +++ current\n    Root (Node)\n      Child (Node2D)\n  -     position = Vector2(1, 2)\n  +     position = Vector2(3, 4)"
+        ),
+        "{message}"
+    );
+}
+
+#[gditest]
+fn resource_snapshot() {
+    let mut resource = Resource::new_gd();
+    resource.set_name("Saved");
+    resource.set_path("res://not_stored.tres");
+
+    let snapshot = snapshot_resource(&resource);
+    assert!(snapshot.starts_with("Resource\n"), "{snapshot}");
+    assert!(
+        snapshot.contains("\n  resource_name = \"Saved\"\n"),
+        "{snapshot}"
+    );
+    // Path depends on where the resource was loaded from.
+    assert!(!snapshot.contains("resource_path"), "{snapshot}");
+    assert!(!snapshot.contains("not_stored.tres"), "{snapshot}");
+}
+
+#[gditest]
+fn soft_assertions(ctx: &TestContext) {
+    let stats = vdict! { "hp": 10, "level": 1 };
+    ctx.check(stats.contains_key("hp"), "missing `hp`");
+    ctx.check_eq(stats.len(), 2);
+    expect!(ctx, stats.contains_key("level"));
+    expect_eq!(ctx, stats.get("level"), Some(1.to_variant()));
+    assert_eq!(ctx.check_failures_count(), 0);
+}
+
+#[gditest]
+fn soft_assertions_collected(ctx: &TestContext) {
+    let stats = vdict! { "hp": 10 };
+    expect!(ctx, stats.contains_key("mana"));
+    expect_eq!(ctx, stats.get("hp"), Some(12.to_variant()), "hp after heal");
+    ctx.check(stats.is_empty(), "stats should be cleared");
+
+    let report = ctx
+        .take_check_failures_report()
+        .expect("checks should fail");
+    assert!(report.starts_with("3 check(s) failed:"), "{report}");
+    let failures = report
+        .lines()
+        .filter(|line| line.starts_with("  at ") && line.contains("itest.rs:"))
+        .collect::<Vec<_>>();
+    assert_eq!(failures.len(), 3, "{report}");
+    assert!(failures[0].contains("`stats.contains_key(\"mana\")` is false"));
+    assert!(failures[1].contains("hp after heal"));
+    assert!(failures[2].contains("stats should be cleared"));
+    assert_eq!(ctx.check_failures_count(), 0);
+}
+
+#[gditest]
+fn property_based(ctx: &TestContext) {
+    use gd_rehearse::itest::generators::*;
+
+    ctx.forall(
+        (vector2(-100.0..100.0), vector2(-100.0..100.0)),
+        |(a, b)| {
+            assert_approx_eq!(a + b, b + a);
+        },
+    );
+    ctx.forall(dictionary(8), |dict| {
+        assert_variant_eq!(dict.duplicate_deep(), dict);
+    });
+    ctx.forall_with(
+        20,
+        (gstring(16), packed_byte_array(32)),
+        |(string, bytes)| {
+            assert_eq!(GString::from(string.to_string().as_str()), string);
+            assert_eq!(bytes.to_vec().len(), bytes.len());
+        },
+    );
+}
+
+#[gditest]
+fn property_based_shrinking(ctx: &TestContext) {
+    use gd_rehearse::itest::generators::*;
+
+    let message = panic_message(|| ctx.forall(any_i64(0..10_000), |value| assert!(value < 100)));
+    assert!(
+        message.contains("shrink step(s)): 100\n"),
+        "should shrink to 100: {message}"
+    );
+    let seed = message
+        .split_once("with seed ")
+        .and_then(|(_, rest)| rest.split('.').next())
+        .and_then(|seed| seed.parse::<u64>().ok())
+        .expect("seed should be printed");
+    assert!(message.contains(&format!("--prop-seed={seed}")));
+}
+
+#[gditest]
+fn property_generators() {
+    use gd_rehearse::itest::generators::*;
+
+    // The same seed reproduces the same inputs.
+    let generator = (any_i64(-100..100), gstring(8), vector2(-1.0..1.0));
+    let generate = |seed| {
+        let mut rng = Rng::new(seed);
+        (0..10)
+            .map(|_| generator.generate(&mut rng))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(generate(42), generate(42));
+    assert_ne!(generate(42), generate(43));
+
+    // Shrinking proposes the simplest candidate first, and stops at it.
+    assert_eq!(any_i64(0..10_000).shrink(&5000), [0, 2500, 4999]);
+    assert_eq!(any_i64(10..20).shrink(&15), [10, 12, 14]);
+    assert!(any_i64(0..10).shrink(&0).is_empty());
+    assert_eq!(any_f64(-10.0..10.0).shrink(&5.5), [0.0, 5.0, 2.75]);
+    assert!(any_f64(-10.0..10.0).shrink(&0.0).is_empty());
+    let shrunk = gstring(8).shrink(&GString::from("abc"));
+    assert_eq!(shrunk.first(), Some(&GString::new()));
+    assert!(shrunk.contains(&GString::from("bc")));
+    assert!(gstring(8).shrink(&GString::new()).is_empty());
+}
@@ -8,6 +8,7 @@ use godot::init::{gdextension, ExtensionLibrary};
 
 mod bench;
 mod itest;
+mod suite;
 
 struct GodotTestTests;
 
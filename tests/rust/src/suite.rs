@@ -0,0 +1,44 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+*/
+
+use gd_rehearse::itest::*;
+use godot::classes::Node;
+use godot::obj::NewAlloc;
+
+fn before_all(ctx: &mut TestContext) {
+    let mut node = Node::new_alloc();
+    let mut child = Node::new_alloc();
+    child.set_name("SharedChild");
+    node.add_child(&child);
+
+    ctx.setup_add_node(node, "SharedNode");
+}
+
+fn after_all(ctx: &mut TestContext) {
+    ctx.remove_all_added_nodes();
+}
+
+gdsuite!(before_all = before_all, after_all = after_all);
+
+#[gditest]
+fn uses_shared_node(ctx: &TestContext) {
+    let shared = ctx.get_node("SharedNode");
+    assert_eq!(shared.get_child_count(), 1);
+}
+
+#[gditest]
+fn uses_shared_child(ctx: &TestContext) {
+    let _child = ctx.get_node("SharedNode/SharedChild");
+}
+
+// Isolated case has its own root, while the suite nodes stay under the runner.
+#[gditest(scene_path = "res://isolated.tscn")]
+fn isolated_uses_suite_node(ctx: &TestContext) {
+    assert_eq!(ctx.scene_tree().get_name(), "CaseRoot".into());
+    let shared = ctx.get_suite_node("SharedNode");
+    assert_eq!(shared.get_child_count(), 1);
+    let _child = ctx.get_suite_node_as::<Node>("SharedNode/SharedChild");
+}